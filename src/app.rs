@@ -1,30 +1,41 @@
-use color_eyre::owo_colors::OwoColorize;
 use rand::prelude::*;
 use std::{
     cmp::Ordering,
-    collections::{BinaryHeap, VecDeque},
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
     sync::{LazyLock, Mutex},
-    thread::sleep,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use hecs::{Entity, Satisfies, With, World};
+use ai::{AiProfile, Decision};
+use encounters::{Encounter, WaveTrigger};
+use events::{CombatEvent, name_span};
+use hecs::{Component, Entity, Satisfies, With, Without, World};
 use hecs_macros::Bundle;
+pub use keymap::KeyMap;
 use ratatui::{
-    crossterm::event::{KeyCode, KeyEvent},
+    crossterm::event::KeyEvent,
+    style::{Color, Stylize},
     text::{Line, Span},
     widgets::{ListState, TableState},
 };
-use skills::Skill;
+use skills::DamageType;
+pub use skills::{Cost, PendingEffects, Skill, max_resources};
 
+mod ai;
+pub mod encounters;
+mod events;
+mod keymap;
 mod skills;
 
 pub struct Log<'a> {
     lines: VecDeque<Line<'a>>,
+    /// Lines written since the start, including those dropped since
+    written: usize,
 }
 pub static LOG: LazyLock<Mutex<Log>> = LazyLock::new(|| {
     Mutex::new(Log {
         lines: VecDeque::with_capacity(100),
+        written: 0,
     })
 });
 
@@ -33,6 +44,7 @@ impl<'a> Log<'a> {
         while self.lines.len() >= 100 {
             self.lines.pop_front();
         }
+        self.written += 1;
         // Deep clone to take ownership of the string
         let style = line.style;
         let alignment = line.alignment;
@@ -47,30 +59,48 @@ impl<'a> Log<'a> {
         });
     }
 
-    pub fn get_lines(&self) -> Vec<Line> {
-        self.lines.iter().map(|s| s.clone()).collect()
+    pub fn get_lines(&self) -> Vec<Line<'_>> {
+        self.lines.iter().cloned().collect()
+    }
+
+    pub fn written(&self) -> usize {
+        self.written
     }
 }
 
 pub enum GameState {
-    Menu,
     Overworld,
     Combat,
+    /// Showing what the party got out of a won fight
+    Victory,
+    /// The whole party is down
+    GameOver,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum CurrentScreen {
     Main,
     Skill,
     Target,
     Item,
     Enemy,
-    Exiting,
+    /// Controls and mechanics, over whichever screen it was opened from
+    Help,
+}
+
+#[derive(Clone, Copy)]
+pub enum Action {
+    Skill,
+    Melee,
+    Item,
+    Defend,
+    Move,
+    Flee,
 }
 
 pub struct ActionListItem {
     pub text: &'static str,
-    pub action: CurrentScreen, // TODO: Might need a separate enum but CurrentScreen is good for now
+    pub action: Action,
 }
 
 pub struct Consumable {
@@ -80,6 +110,9 @@ pub struct Consumable {
 }
 
 pub struct App {
+    pub keys: KeyMap,
+    /// Plain ASCII in place of icons, for terminals that can't draw them
+    pub ascii: bool,
     pub game_state: GameState,
     pub current_screen: CurrentScreen,
     pub previous_screen: Vec<CurrentScreen>,
@@ -87,6 +120,12 @@ pub struct App {
     pub consumables: Vec<Consumable>,
     pub turn: Option<Entity>,
     pub next_up: Option<NextUp>,
+    /// Turns taken this fight
+    pub turn_number: u32,
+    /// A round ends once everyone still standing has acted
+    pub round_number: u32,
+    /// Who has acted this round
+    pub acted: HashSet<Entity>,
     pub action_list_items: &'static [ActionListItem],
     pub action_list_state: ListState,
     pub skill_list_state: ListState,
@@ -94,6 +133,155 @@ pub struct App {
     pub targets: Vec<Entity>,
     pub selected_target: Option<usize>,
     pub skill: Option<&'static Skill>,
+    pub notice: Option<&'static str>,
+    pub weakness_struck: bool,
+    pub bonus_turn: bool,
+    /// Statuses the actor picked up during its own turn, which don't tick down until its next one
+    fresh_statuses: Vec<&'static str>,
+    pub advantage: Advantage,
+    /// Bosses and other set pieces can't be run from
+    pub can_flee: bool,
+    /// Attempts to flee this fight that didn't work out
+    failed_flees: u8,
+    /// When the enemy whose turn it is gets to act
+    enemy_acts_at: Option<Instant>,
+    /// Encounter the debug key starts next
+    next_encounter: usize,
+    pub banner: Option<Banner>,
+    pub popups: Vec<Popup>,
+    /// One per entity, so a flurry of hits restarts the flash rather than lining up more
+    flashes: HashMap<Entity, Flash>,
+    /// The latest change in each entity's health, kept after it's done to spot the next one
+    health_shifts: HashMap<Entity, HealthShift>,
+    /// Whether the last tick had anything moving, so the frame it settles on still gets drawn
+    was_animating: bool,
+    /// Seconds on the fight clock as of the last tick
+    clock: u64,
+    /// Set once the player has chosen to quit, for the run loop to stop on
+    pub exiting: bool,
+    pub confirmation: Option<Confirmation>,
+    /// Rows the help overlay is scrolled down by
+    pub help_scroll: u16,
+    pub summary: CombatSummary,
+    /// The fight in progress, or the last one
+    pub encounter: Option<&'static Encounter>,
+    /// When the fight in progress started
+    pub combat_started: Instant,
+    /// The encounter's first wave that hasn't arrived yet
+    next_wave: usize,
+    /// The party as it was when the fight started, to put back if it's retried
+    party_snapshot: Vec<PartySnapshot>,
+    pub game_over_list_state: ListState,
+    /// Rows the log is scrolled up by. At 0 it follows new lines as they come in.
+    pub log_scroll: u16,
+    /// Lines written when the log was scrolled up, to tell if new ones came in since
+    pub log_seen: usize,
+    /// Rows the log took up when last drawn, to keep a scrolled view still as lines come in
+    pub log_rows: u16,
+    /// Every roll in combat comes from here, so a seed replays the same fights
+    rng: StdRng,
+}
+
+pub const GAME_OVER_OPTIONS: [&str; 2] = ["Retry encounter", "Quit"];
+
+struct PartySnapshot {
+    entity: Entity,
+    health: u32,
+    job: Job,
+    dead: bool,
+}
+
+/// What the party got out of the current fight, shown once it's won
+#[derive(Default)]
+pub struct CombatSummary {
+    pub defeated: Vec<&'static str>,
+    /// XP earned by each character, and the XP they had before
+    pub xp: Vec<(&'static str, u32, u32)>,
+    /// Characters who leveled up and their new level
+    pub level_ups: Vec<(&'static str, u8)>,
+    pub loot: Vec<&'static str>,
+    /// What the defeated are worth, handed out once the fight is won
+    xp_pool: u32,
+}
+
+/// Share of the XP the fallen get compared to those still standing
+const DEAD_XP_SHARE: f32 = 0.5;
+/// Chance a defeated enemy drops something from `LOOT_TABLE`
+const LOOT_CHANCE: f64 = 0.3;
+static LOOT_TABLE: [(&str, &LazyLock<Skill>); 3] = [
+    ("Potion", &skills::common::POTION),
+    ("Cleanse", &skills::common::CLEANSE),
+    ("EMP Grenade", &skills::common::EMP_GRENADE),
+];
+
+/// Big announcement shown over the field for a moment
+pub struct Banner {
+    pub text: Span<'static>,
+    pub until: Instant,
+}
+
+const BANNER_DURATION: Duration = Duration::from_secs(2);
+
+/// A yes-or-no question that holds up all other input until it's answered
+pub struct Confirmation {
+    pub title: &'static str,
+    pub body: String,
+    pub on_confirm: Message,
+    /// Backing out does nothing beyond closing it unless there's something to go back to
+    pub on_cancel: Option<Message>,
+    /// Answered with this instead of Select, like q twice to quit
+    pub confirm_with: Option<Message>,
+}
+
+/// A damage or healing number floating over whoever took it
+pub struct Popup {
+    pub target: Entity,
+    pub text: Span<'static>,
+    shown_at: Instant,
+}
+
+const POPUP_DURATION: Duration = Duration::from_millis(600);
+
+/// A struck entity's card lighting up for a few frames
+pub struct Flash {
+    pub color: Color,
+    shown_at: Instant,
+}
+
+const FLASH_DURATION: Duration = Duration::from_millis(150);
+
+/// A health bar catching up to a change in health
+#[derive(Clone, Copy)]
+pub struct HealthShift {
+    pub from: u32,
+    pub to: u32,
+    started: Instant,
+}
+
+const HEALTH_SHIFT_DURATION: Duration = Duration::from_millis(400);
+
+impl HealthShift {
+    /// Health the bar shows right now, partway from the old value to the new
+    pub fn shown(&self) -> u32 {
+        let progress =
+            (self.started.elapsed().as_secs_f64() / HEALTH_SHIFT_DURATION.as_secs_f64()).min(1.);
+        (self.from as f64 + (self.to as f64 - self.from as f64) * progress).round() as u32
+    }
+
+    fn done(&self) -> bool {
+        self.started.elapsed() >= HEALTH_SHIFT_DURATION
+    }
+}
+
+impl Popup {
+    /// Rows the number has drifted up by
+    pub fn rise(&self) -> u16 {
+        if self.shown_at.elapsed() >= POPUP_DURATION / 2 {
+            1
+        } else {
+            0
+        }
+    }
 }
 
 // Basic
@@ -103,20 +291,111 @@ pub struct Name(pub &'static str);
 pub struct Xp(pub u32);
 #[derive(Default)]
 pub struct Level(pub u8);
+/// XP an enemy is worth when defeated
+pub struct XpReward(pub u32);
 #[derive(Default)]
 pub struct Health(pub u32);
 
 // Stats
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy)]
 pub struct Stats {
     pub max_health: u32,
     pub attack: u32,
     pub speed: u32,
     pub crit: f32,
+    /// Crit damage relative to `BASE_CRIT_DAMAGE`, scales each skill's own crit multiplier
+    pub crit_damage: f32,
+    pub accuracy: f32,
     pub evade: f32,
     pub defense: u32,
 }
 
+/// Crit damage skills' crit multipliers are balanced around
+pub const BASE_CRIT_DAMAGE: f32 = 1.5;
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            max_health: 0,
+            attack: 0,
+            speed: 0,
+            crit: 0.,
+            crit_damage: BASE_CRIT_DAMAGE,
+            // Hit chance is accuracy minus the target's evade
+            accuracy: 1.,
+            evade: 0.,
+            defense: 0,
+        }
+    }
+}
+
+/// Damage multipliers per `DamageType`. Below 1 resists, above 1 is a weakness and 0 is immunity.
+#[derive(Clone, Copy)]
+pub struct Resistances([f32; 8]);
+
+impl Default for Resistances {
+    fn default() -> Self {
+        Self([1.; 8])
+    }
+}
+
+impl Resistances {
+    pub fn with(mut self, damage_type: DamageType, multiplier: f32) -> Self {
+        self.0[damage_type as usize] = multiplier;
+        self
+    }
+
+    pub fn get(&self, damage_type: DamageType) -> f32 {
+        self.0[damage_type as usize]
+    }
+
+    /// Damage types it takes extra damage from, then the ones it takes less from
+    pub fn affinities(&self) -> (Vec<DamageType>, Vec<DamageType>) {
+        let weak = DamageType::ALL
+            .into_iter()
+            .filter(|&damage_type| self.get(damage_type) > 1.)
+            .collect();
+        let resists = DamageType::ALL
+            .into_iter()
+            .filter(|&damage_type| self.get(damage_type) < 1.)
+            .collect();
+        (weak, resists)
+    }
+}
+
+/// Debuffs the entity can't be afflicted with, by status name. With diminishing returns every
+/// stun counts towards becoming immune to stuns for the rest of the fight.
+#[derive(Default)]
+pub struct Immunities {
+    statuses: Vec<&'static str>,
+    /// Stuns it takes to become immune to them
+    diminishing: Option<u8>,
+    stuns: u8,
+}
+
+impl Immunities {
+    pub fn with<T: Status>(mut self) -> Self {
+        self.statuses.push(T::NAME);
+        self
+    }
+
+    pub fn diminishing(mut self, stuns: u8) -> Self {
+        self.diminishing = Some(stuns);
+        self
+    }
+
+    pub fn contains(&self, status: &str) -> bool {
+        self.statuses.contains(&status)
+    }
+
+    pub fn stunned(&mut self) {
+        self.stuns = self.stuns.saturating_add(1);
+        if self.diminishing.is_some_and(|limit| self.stuns >= limit) {
+            self.statuses.push(Stunned::NAME);
+        }
+    }
+}
+
 // Resources
 #[derive(Clone, Copy, Default)]
 pub enum Job {
@@ -144,6 +423,10 @@ pub enum Job {
 #[derive(Default)]
 pub struct Skills(pub Vec<&'static Skill>);
 
+/// Turns left on each skill the entity has used recently, keyed by skill name.
+#[derive(Default)]
+pub struct Cooldowns(pub HashMap<&'static str, u8>);
+
 // Misc
 #[derive(Default)]
 pub struct Party;
@@ -151,14 +434,277 @@ pub struct Party;
 pub struct Hostile;
 #[derive(Default)]
 pub struct Initiative(pub f32);
+/// Damage recently dealt to enemies, which draws their attention. Decays every turn.
+#[derive(Default)]
+pub struct Threat(pub f32);
+/// Where in the formation an entity stands. The back row takes and deals less physical damage.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Row {
+    #[default]
+    Front,
+    Back,
+}
 pub struct Dead;
+/// Brought in by a skill mid-fight, gone once the fight is over
+pub struct Summoned;
+/// Health shows up on the enemy's card, and with resistances its affinities show too. Only lasts
+/// for the fight it was scanned in.
+pub struct Scanned {
+    pub resistances: bool,
+}
+/// Keeps its resistances to itself even when deep scanned
+pub struct Unscannable;
+/// Stand-in for a caster that's gone by the time its delayed effects go off. Despawned along
+/// with the dead, once its events have been logged.
+pub struct Ghost;
 
 // Status
-pub struct Burning(pub u8);
-pub struct Frozen;
-pub struct Confused;
-pub struct Blind;
-pub struct Stunned;
+/// Deals fire damage per stack each turn until the turns run out
+pub struct Burning {
+    pub stacks: u8,
+    pub turns: u8,
+}
+pub struct Frozen(pub u8);
+pub struct Contagious(pub u8);
+pub struct Zapped(pub u8);
+pub struct Regen {
+    #[allow(dead_code)] // Nothing applies Regen yet
+    pub amount: u32,
+    pub duration: u8,
+}
+pub struct Stunned(pub u8);
+pub struct Slowed(pub u8);
+pub struct Confused(pub u8);
+pub struct Blind(pub u8);
+
+// Buffs
+pub struct Hasted(pub u8);
+pub struct Shelled(pub u8);
+/// Enemies have to target taunting party members
+pub struct Taunting(pub u8);
+/// Absorbs damage before health until depleted
+pub struct Barrier(pub u32);
+/// Takes half damage until the start of the entity's next turn
+pub struct Defending;
+/// Casting a charged skill, which fires at the start of the entity's next turn
+pub struct Charging {
+    pub skill: &'static Skill,
+    pub targets: Vec<Entity>,
+}
+/// An enemy that changes how it fights as its health drops
+pub struct Boss {
+    pub phases: Vec<Phase>,
+    pub phase: usize,
+}
+pub struct Phase {
+    /// Health fraction the boss has to drop to for the phase to start
+    pub threshold: f32,
+    pub announcement: &'static str,
+    pub ai: AiProfile,
+    pub skills: Vec<&'static Skill>,
+    /// Used by the boss on itself once, as the phase starts
+    pub transition: Option<&'static Skill>,
+}
+/// Uses the skill for free when an ally's action sets off the trigger
+pub struct Readied {
+    pub skill: &'static Skill,
+    pub trigger: skills::Trigger,
+    pub turns: u8,
+}
+
+/// Answers the next opposing skill that sets off the trigger, before it resolves. Wears off at the
+/// end of the round if nothing does.
+pub struct Reaction {
+    pub name: &'static str,
+    pub trigger: skills::Interrupt,
+    pub response: skills::Response,
+}
+
+/// Statuses that wear off after a number of turns.
+pub trait Status: Component {
+    const NAME: &'static str;
+    /// Shown along with the turns left wherever there's no room for the name
+    const ICON: &'static str;
+    /// Stands in for the icon on terminals that can't draw it
+    const ASCII: &'static str;
+    fn turns(&mut self) -> &mut u8;
+    fn stacks(&self) -> u8 {
+        1
+    }
+}
+
+impl Status for Burning {
+    const NAME: &'static str = "Burning";
+    const ICON: &'static str = "🔥";
+    const ASCII: &'static str = "B";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.turns
+    }
+    fn stacks(&self) -> u8 {
+        self.stacks
+    }
+}
+
+/// Burning stacks past this are lost
+pub const MAX_BURNING_STACKS: u8 = 5;
+
+impl Status for Frozen {
+    const NAME: &'static str = "Frozen";
+    const ICON: &'static str = "❄";
+    const ASCII: &'static str = "F";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Contagious {
+    const NAME: &'static str = "Contagious";
+    const ICON: &'static str = "☣";
+    const ASCII: &'static str = "C";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Zapped {
+    const NAME: &'static str = "Zapped";
+    const ICON: &'static str = "ϟ";
+    const ASCII: &'static str = "Z";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Regen {
+    const NAME: &'static str = "Regen";
+    const ICON: &'static str = "✚";
+    const ASCII: &'static str = "R";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.duration
+    }
+}
+
+impl Status for Stunned {
+    const NAME: &'static str = "Stunned";
+    const ICON: &'static str = "✶";
+    const ASCII: &'static str = "St";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Slowed {
+    const NAME: &'static str = "Slowed";
+    const ICON: &'static str = "⧗";
+    const ASCII: &'static str = "Sl";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Confused {
+    const NAME: &'static str = "Confused";
+    const ICON: &'static str = "?";
+    const ASCII: &'static str = "?";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Blind {
+    const NAME: &'static str = "Blind";
+    const ICON: &'static str = "◌";
+    const ASCII: &'static str = "Bl";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Hasted {
+    const NAME: &'static str = "Hasted";
+    const ICON: &'static str = "»";
+    const ASCII: &'static str = "H";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Shelled {
+    const NAME: &'static str = "Shelled";
+    const ICON: &'static str = "🛡";
+    const ASCII: &'static str = "Sh";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Taunting {
+    const NAME: &'static str = "Taunting";
+    const ICON: &'static str = "💢";
+    const ASCII: &'static str = "T";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.0
+    }
+}
+
+impl Status for Readied {
+    const NAME: &'static str = "Readied";
+    const ICON: &'static str = "⌖";
+    const ASCII: &'static str = "Rd";
+    fn turns(&mut self) -> &mut u8 {
+        &mut self.turns
+    }
+}
+
+pub struct StatusKind {
+    pub name: &'static str,
+    pub icon: &'static str,
+    pub ascii: &'static str,
+    /// Returns the name of the status if it wore off.
+    tick: fn(&mut World, Entity) -> Option<&'static str>,
+    /// Turns the entity has left of the status, if it has it
+    pub turns_left: fn(&World, Entity) -> Option<u8>,
+    pub stacks: fn(&World, Entity) -> u8,
+    /// Returns the name of the status if the entity had it.
+    pub remove: fn(&mut World, Entity) -> Option<&'static str>,
+}
+
+impl StatusKind {
+    const fn of<T: Status>() -> Self {
+        Self {
+            name: T::NAME,
+            icon: T::ICON,
+            ascii: T::ASCII,
+            tick: tick::<T>,
+            turns_left: turns_left::<T>,
+            stacks: stacks::<T>,
+            remove: remove::<T>,
+        }
+    }
+}
+
+/// Every debuff component. Ticking and cleansing both go through this list, so new debuffs only
+/// need to be added here.
+pub const DEBUFFS: [StatusKind; 8] = [
+    StatusKind::of::<Burning>(),
+    StatusKind::of::<Frozen>(),
+    StatusKind::of::<Contagious>(),
+    StatusKind::of::<Zapped>(),
+    StatusKind::of::<Stunned>(),
+    StatusKind::of::<Slowed>(),
+    StatusKind::of::<Confused>(),
+    StatusKind::of::<Blind>(),
+];
+
+/// Every buff component. Regen is applied like a debuff but is beneficial, so it lives here where
+/// cleansing won't touch it.
+pub const BUFFS: [StatusKind; 5] = [
+    StatusKind::of::<Regen>(),
+    StatusKind::of::<Hasted>(),
+    StatusKind::of::<Shelled>(),
+    StatusKind::of::<Taunting>(),
+    StatusKind::of::<Readied>(),
+];
 
 #[derive(Clone, PartialEq)]
 pub struct InitiativeInfo {
@@ -213,23 +759,34 @@ impl Iterator for NextUp {
     }
 }
 
+#[derive(Clone, Copy)]
 pub enum Advantage {
     Friendly,
     Enemy,
     Neutral,
 }
 
+#[derive(Clone, Copy, PartialEq)]
 pub enum Message {
     Up,
     Down,
     Left,
     Right,
-    Prev,
-    Next,
     Select,
     Cancel,
     Quit,
-    Think,
+    /// Quitting for real, once it's been confirmed
+    Exit,
+    Flee,
+    /// Goes ahead with the item on the chosen targets, even if it's the last one
+    UseItem,
+    /// Debug builds only, until the overworld exists
+    NextEncounter,
+    LogUp,
+    LogDown,
+    /// Back to the bottom of the log, following new lines again
+    LogEnd,
+    Help,
 }
 
 #[derive(Bundle, Default)]
@@ -237,11 +794,14 @@ struct CharacterBundle {
     name: Name,
     job: Job,
     skills: Skills,
+    cooldowns: Cooldowns,
     health: Health,
     level: Level,
     xp: Xp,
     stats: Stats,
     initiative: Initiative,
+    threat: Threat,
+    row: Row,
     party: Party,
 }
 
@@ -250,86 +810,364 @@ struct NPCBundle {
     name: Name,
     health: Health,
     skills: Skills,
+    cooldowns: Cooldowns,
     level: Level,
     xp: Xp,
     stats: Stats,
     initiative: Initiative,
+    resistances: Resistances,
+    immunities: Immunities,
+    ai: AiProfile,
+    row: Row,
     hostile: Hostile,
 }
 
-const LEVEL_THRESHOLDS: [u32; 10] = [0, 100, 300, 600, 1000, 1500, 2100, 2800, 3600, 4500];
+/// Total XP needed to go from a level to the next one. Grows with the level.
+const fn next_level_xp(level: u8) -> u32 {
+    50 * level as u32 * (level as u32 + 1)
+}
 
-fn level_up(world: &mut World) {
-    for (_, (Level(level), &Xp(xp), stats, Health(health))) in
-        world.query_mut::<(&mut Level, &Xp, &mut Stats, &mut Health)>()
-    {
-        if xp >= LEVEL_THRESHOLDS[*level as usize] {
-            *level += 1;
-            stats.max_health = 80 + 20 * *level as u32;
-            stats.attack = 16 + 4 * *level as u32;
-            stats.speed = 100 + 20 * *level as u32;
-            stats.crit = 0.1 + 0.05 * *level as f32;
-            *health = stats.max_health;
+pub const MAX_LEVEL: u8 = 99;
+
+/// The level that much total XP makes for
+fn level_at(xp: u32) -> u8 {
+    let mut level = 0;
+    while level < MAX_LEVEL && xp >= next_level_xp(level) {
+        level += 1;
+    }
+    level
+}
+
+/// XP earned since the last level and XP the next level takes, or nothing at the max level
+pub fn xp_progress(xp: u32) -> Option<(u32, u32)> {
+    let level = level_at(xp);
+    if level >= MAX_LEVEL {
+        return None;
+    }
+    let floor = next_level_xp(level - 1);
+    Some((xp - floor, next_level_xp(level) - floor))
+}
+
+/// How much a character's stats grow with each level
+struct Growth {
+    max_health: u32,
+    attack: u32,
+    speed: u32,
+    crit: f32,
+}
+
+impl Growth {
+    const fn of(job: Option<&Job>) -> Self {
+        match job {
+            Some(Job::Gunslinger { .. }) => Growth {
+                max_health: 18,
+                attack: 4,
+                speed: 20,
+                crit: 0.07,
+            },
+            Some(Job::Netrunner { .. }) => Growth {
+                max_health: 16,
+                attack: 5,
+                speed: 20,
+                crit: 0.05,
+            },
+            Some(Job::Technopriest { .. }) => Growth {
+                max_health: 26,
+                attack: 3,
+                speed: 18,
+                crit: 0.04,
+            },
+            Some(Job::Clairvoyant { .. }) => Growth {
+                max_health: 18,
+                attack: 5,
+                speed: 18,
+                crit: 0.05,
+            },
+            Some(Job::Nanovampire { .. }) => Growth {
+                max_health: 20,
+                attack: 4,
+                speed: 26,
+                crit: 0.05,
+            },
+            Some(Job::None) | None => Growth {
+                max_health: 20,
+                attack: 4,
+                speed: 20,
+                crit: 0.05,
+            },
+        }
+    }
+}
+
+/// Levels up everyone with enough XP, as many times as it's enough for, returning who leveled.
+fn level_up(world: &mut World) -> Vec<Entity> {
+    let mut leveled = Vec::new();
+    for (entity, (Level(level), &Xp(xp), stats, Health(health), job, party)) in world.query_mut::<(
+        &mut Level,
+        &Xp,
+        &mut Stats,
+        &mut Health,
+        Option<&Job>,
+        Satisfies<&Party>,
+    )>() {
+        if level_at(xp) <= *level {
+            continue;
+        }
+        *level = level_at(xp);
+        let growth = Growth::of(job);
+        let level = *level as u32;
+        stats.max_health = 80 + growth.max_health * level;
+        stats.attack = 16 + growth.attack * level;
+        stats.speed = 100 + growth.speed * level;
+        stats.crit = 0.1 + growth.crit * level as f32;
+        stats.crit_damage = BASE_CRIT_DAMAGE + 0.05 * (level - 1) as f32;
+        if party {
+            stats.evade = 0.05 + 0.01 * level as f32;
+        }
+        *health = stats.max_health;
+        leveled.push(entity);
+    }
+    for &entity in &leveled {
+        learn_skills(world, entity);
+    }
+    leveled
+}
+
+fn learn_skills(world: &mut World, entity: Entity) {
+    let Ok(mut query) = world.query_one::<(&Name, &Job, &Level, &mut Skills)>(entity) else {
+        return;
+    };
+    let Some((&Name(name), job, &Level(level), Skills(known))) = query.get() else {
+        return;
+    };
+    let unlocked = skills::skills_for(job, level);
+    // Starting skills aren't worth announcing
+    if level > 1 {
+        let mut log = LOG.lock().unwrap();
+        for skill in unlocked
+            .iter()
+            .filter(|skill| !known.iter().any(|k| k.name == skill.name))
+        {
+            log.write(Line::from(format!("{name} learned {}!", skill.name).bold()));
+        }
+    }
+    *known = unlocked;
+}
+
+/// Part of a turn a defending entity gets back, so defending isn't a wasted action
+const DEFEND_REBATE: f32 = 0.25;
+
+/// Chance to flee when the party is exactly as fast as the enemy
+const BASE_FLEE_CHANCE: f32 = 0.5;
+/// Added to the chance to flee for every failed attempt in the same fight
+const FLEE_RETRY_BONUS: f32 = 0.15;
+
+/// Chance the party gets away, from how its fastest member compares to the fastest enemy. Each
+/// failed attempt in the same fight makes the next one likelier.
+pub fn escape_chance(world: &World, can_flee: bool, advantage: Advantage, failed: u8) -> f32 {
+    if !can_flee {
+        return 0.;
+    }
+    let fastest = |hostile: bool| {
+        world
+            .query::<Without<(&Stats, Satisfies<&Hostile>), &Dead>>()
+            .iter()
+            .filter(|(_, (_, other))| *other == hostile)
+            .map(|(entity, _)| effective_speed(world, entity))
+            .max()
+            .unwrap_or(0) as f32
+    };
+    let mut chance = BASE_FLEE_CHANCE * fastest(false) / fastest(true).max(1.);
+    if matches!(advantage, Advantage::Enemy) {
+        chance /= 2.;
+    }
+    (chance + FLEE_RETRY_BONUS * failed as f32).clamp(0.05, 0.95)
+}
+
+/// Chance a big hit or a stun breaks a charging entity's concentration
+const INTERRUPT_CHANCE: f64 = 0.5;
+
+/// Entities frozen this much lose their turns until they thaw.
+const FROZEN_SOLID: u8 = 3;
+
+/// Netrunners this hot lose their next turn to a thermal shutdown, which resets their heat.
+pub const OVERHEAT: u8 = 100;
+/// Heat a Netrunner sheds at the end of each of their turns
+const HEAT_DISSIPATION: u8 = 5;
+
+/// Where a combatant is drawn: enemies left to right, then the party top to bottom. Picking
+/// targets walks the same order, so the cursor moves the way the field looks.
+pub fn field_position(world: &World, entity: Entity) -> (bool, u32) {
+    (
+        !world.satisfies::<&Hostile>(entity).unwrap_or(false),
+        entity.id(),
+    )
+}
+
+/// Average speed of the living on one side of the fight
+fn average_speed(world: &World, hostile: bool) -> f64 {
+    let speeds = world
+        .query::<Without<(&Stats, Satisfies<&Hostile>), &Dead>>()
+        .iter()
+        .filter(|(_, (_, other))| *other == hostile)
+        .map(|(entity, _)| effective_speed(world, entity) as f64)
+        .collect::<Vec<_>>();
+    speeds.iter().sum::<f64>() / speeds.len().max(1) as f64
+}
+
+/// Chance per times faster one side is than the other that it gets the jump on them
+const AMBUSH_CHANCE: f64 = 0.15;
+/// Highest chance either side has of getting the jump on the other
+const MAX_AMBUSH_CHANCE: f64 = 0.5;
+
+/// Who gets the jump on whom in a fight that doesn't decide it itself. The faster side is more
+/// likely to.
+fn roll_advantage(world: &World, rng: &mut impl Rng) -> Advantage {
+    let ratio = average_speed(world, false) / average_speed(world, true).max(1.);
+    let roll = rng.random::<f64>();
+    if roll < (AMBUSH_CHANCE * ratio).min(MAX_AMBUSH_CHANCE) {
+        Advantage::Friendly
+    } else if 1. - roll < (AMBUSH_CHANCE / ratio.max(0.01)).min(MAX_AMBUSH_CHANCE) {
+        Advantage::Enemy
+    } else {
+        Advantage::Neutral
+    }
+}
+
+/// Speed after statuses that speed the entity up or slow it down. Used both for the Next Up
+/// preview and for advancing initiative, so the two never diverge.
+fn effective_speed(world: &World, entity: Entity) -> u32 {
+    let mut speed = world.get::<&Stats>(entity).map_or(0, |stats| stats.speed);
+    if world.satisfies::<&Hasted>(entity).unwrap_or(false) {
+        speed *= 2;
+    }
+    if world.satisfies::<&Frozen>(entity).unwrap_or(false) {
+        speed /= 2;
+    }
+    if world.satisfies::<&Slowed>(entity).unwrap_or(false) {
+        speed /= 2;
+    }
+    speed
+}
+
+fn tick<T: Status>(world: &mut World, entity: Entity) -> Option<&'static str> {
+    let expired = world.get::<&mut T>(entity).is_ok_and(|mut status| {
+        let turns = status.turns();
+        *turns = turns.saturating_sub(1);
+        *turns == 0
+    });
+    expired.then(|| {
+        world.remove_one::<T>(entity).unwrap();
+        T::NAME
+    })
+}
+
+fn turns_left<T: Status>(world: &World, entity: Entity) -> Option<u8> {
+    world
+        .get::<&mut T>(entity)
+        .ok()
+        .map(|mut status| *status.turns())
+}
+
+fn stacks<T: Status>(world: &World, entity: Entity) -> u8 {
+    world.get::<&T>(entity).map_or(0, |status| status.stacks())
+}
+
+fn remove<T: Status>(world: &mut World, entity: Entity) -> Option<&'static str> {
+    world.remove_one::<T>(entity).ok().map(|_| T::NAME)
+}
+
+/// Ticks down every status on the entity apart from those in `fresh`
+fn tick_statuses(
+    world: &mut World,
+    entity: Entity,
+    fresh: &[&'static str],
+    events: &mut Vec<CombatEvent>,
+) {
+    for (statuses, harmful) in [(&DEBUFFS[..], true), (&BUFFS[..], false)] {
+        for status in statuses {
+            if fresh.contains(&status.name) {
+                continue;
+            }
+            if let Some(status) = (status.tick)(world, entity) {
+                events.push(CombatEvent::WoreOff {
+                    target: entity,
+                    status,
+                    harmful,
+                });
+            }
         }
     }
 }
 
+fn tick_cooldowns(world: &mut World, entity: Entity) {
+    if let Ok(mut cooldowns) = world.get::<&mut Cooldowns>(entity) {
+        cooldowns.0.retain(|_, turns| {
+            *turns -= 1;
+            *turns > 0
+        });
+    }
+}
+
+/// Rows a PageUp or PageDown scrolls the log by
+const LOG_PAGE: u16 = 5;
+
+/// How long an enemy waits before acting
+const ENEMY_DELAY: Duration = Duration::from_secs(1);
+
+/// Part of its threat an entity keeps at the end of each of its turns
+const THREAT_DECAY: f32 = 0.5;
+
+fn decay_threat(world: &mut World, entity: Entity) {
+    if let Ok(mut threat) = world.get::<&mut Threat>(entity) {
+        threat.0 *= THREAT_DECAY;
+    }
+}
+
+fn dissipate_heat(world: &mut World, entity: Entity) {
+    if let Ok(mut job) = world.get::<&mut Job>(entity)
+        && let Job::Netrunner { heat, .. } = &mut *job
+        && *heat < OVERHEAT
+    {
+        *heat = heat.saturating_sub(HEAT_DISSIPATION);
+    }
+}
+
 fn spawn_party(world: &mut World) {
     world.spawn(CharacterBundle {
         name: Name("Gunslinger"),
         job: Job::Gunslinger { ammo: 6 },
-        skills: Skills(vec![
-            &skills::gunslinger::RELOAD,
-            &skills::gunslinger::TACTICAL_RELOAD,
-            &skills::gunslinger::DOUBLE_TAP,
-        ]),
         ..Default::default()
     });
-    // world.spawn(CharacterBundle {
-    //     name: Name("Netrunner"),
-    //     job: Job::Netrunner { ram: 16, heat: 54 },
-    //     ..Default::default()
-    // });
     world.spawn(CharacterBundle {
-        name: Name("Technopriest"),
-        job: Job::Technopriest { prayers: 4 },
+        name: Name("Netrunner"),
+        job: Job::Netrunner { ram: 16, heat: 0 },
         ..Default::default()
     });
-    // world.spawn(CharacterBundle {
-    //     name: Name("Clairvoyant"),
-    //     job: Job::Clairvoyant { sun: 0, moon: 0 },
-    //     ..Default::default()
-    // });
     world.spawn(CharacterBundle {
-        name: Name("Nanovampire"),
-        job: Job::Nanovampire { battery: 100 },
-        ..Default::default()
-    });
-
-    level_up(world);
-}
-
-fn spawn_enemies(world: &mut World) {
-    world.spawn(NPCBundle {
-        name: Name("Sewer Rat"),
+        name: Name("Technopriest"),
+        job: Job::Technopriest { prayers: 4 },
+        row: Row::Back,
         ..Default::default()
     });
-    world.spawn(NPCBundle {
-        name: Name("Cybermutant"),
+    world.spawn(CharacterBundle {
+        name: Name("Clairvoyant"),
+        job: Job::Clairvoyant { sun: 0, moon: 0 },
+        row: Row::Back,
         ..Default::default()
     });
-    let rat = world.spawn(NPCBundle {
-        name: Name("Sewer Rat".into()),
+    world.spawn(CharacterBundle {
+        name: Name("Nanovampire"),
+        job: Job::Nanovampire { battery: 100 },
         ..Default::default()
     });
 
-    world.insert_one(rat, Burning(3)).unwrap();
-
     level_up(world);
 }
 
 impl App {
-    pub fn new() -> App {
+    /// Seeded from entropy unless a seed is given
+    pub fn new(seed: Option<u64>, keys: KeyMap, ascii: bool) -> App {
         let mut world = World::new();
 
         spawn_party(&mut world);
@@ -350,9 +1188,21 @@ impl App {
                 amount: 3,
                 skill: &skills::common::REVIVE,
             },
+            Consumable {
+                name: "EMP Grenade",
+                amount: 2,
+                skill: &skills::common::EMP_GRENADE,
+            },
+            Consumable {
+                name: "Scanner",
+                amount: 5,
+                skill: &skills::common::SCAN,
+            },
         ];
 
         App {
+            keys,
+            ascii,
             game_state: GameState::Combat,
             current_screen: CurrentScreen::Main,
             previous_screen: Vec::new(),
@@ -360,6 +1210,9 @@ impl App {
             consumables,
             turn: None,
             next_up: None,
+            turn_number: 0,
+            round_number: 1,
+            acted: HashSet::new(),
             action_list_items: &[],
             action_list_state: ListState::default().with_selected(Some(0)),
             skill_list_state: ListState::default().with_selected(Some(0)),
@@ -367,39 +1220,140 @@ impl App {
             targets: Vec::new(),
             selected_target: None,
             skill: None,
+            notice: None,
+            weakness_struck: false,
+            bonus_turn: false,
+            fresh_statuses: Vec::new(),
+            advantage: Advantage::Neutral,
+            can_flee: true,
+            failed_flees: 0,
+            enemy_acts_at: None,
+            next_encounter: 0,
+            banner: None,
+            popups: Vec::new(),
+            flashes: HashMap::new(),
+            health_shifts: HashMap::new(),
+            was_animating: false,
+            clock: 0,
+            exiting: false,
+            confirmation: None,
+            help_scroll: 0,
+            summary: CombatSummary::default(),
+            encounter: None,
+            combat_started: Instant::now(),
+            next_wave: 0,
+            party_snapshot: Vec::new(),
+            game_over_list_state: ListState::default().with_selected(Some(0)),
+            log_scroll: 0,
+            log_seen: 0,
+            log_rows: 0,
+            rng: seed.map_or_else(StdRng::from_os_rng, StdRng::seed_from_u64),
         }
     }
 
+    pub fn random_encounter(&mut self) -> &'static Encounter {
+        encounters::random(&mut self.rng)
+    }
+
     pub fn handle_key(&self, key: KeyEvent) -> Option<Message> {
-        match key.code {
-            KeyCode::Char('q') => Some(Message::Quit),
-            KeyCode::Esc => Some(Message::Cancel),
-            KeyCode::Up => Some(Message::Up),
-            KeyCode::Down => Some(Message::Down),
-            KeyCode::Left => Some(Message::Left),
-            KeyCode::Right => Some(Message::Right),
-            KeyCode::Enter => Some(Message::Select),
-            _ => None,
-        }
+        self.keys.get(key)
     }
 
-    pub fn update(&mut self, message: Message) -> Option<Message> {
+    /// Acts on a message, returning whether anything on screen changed and needs a redraw
+    pub fn update(&mut self, message: Message) -> bool {
+        let had_notice = self.notice.take().is_some();
+        if let Some(confirmation) = &self.confirmation {
+            let answer = if message == confirmation.confirm_with.unwrap_or(Message::Select) {
+                Some(confirmation.on_confirm)
+            } else if message == Message::Cancel {
+                confirmation.on_cancel
+            } else {
+                return had_notice;
+            };
+            self.confirmation = None;
+            if let Some(answer) = answer {
+                self.update(answer);
+            }
+            return true;
+        }
         match message {
+            // Too easy to hit by accident while dismissing the summary
+            Message::Quit | Message::Cancel if matches!(self.game_state, GameState::Victory) => {
+                return had_notice;
+            }
             Message::Quit => {
-                if matches!(self.current_screen, CurrentScreen::Exiting) {
-                    return Some(Message::Quit);
-                } else {
-                    self.previous_screen.push(self.current_screen);
-                    self.current_screen = CurrentScreen::Exiting;
-                    return None;
+                self.confirmation = Some(Confirmation {
+                    title: "Really quit?",
+                    body: format!(
+                        "Press {} again to confirm",
+                        self.keys.hint(&[Message::Quit])
+                    ),
+                    on_confirm: Message::Exit,
+                    on_cancel: None,
+                    confirm_with: Some(Message::Quit),
+                });
+                return true;
+            }
+            Message::Exit => {
+                self.exiting = true;
+                return true;
+            }
+            Message::Flee => {
+                self.flee();
+                return true;
+            }
+            Message::UseItem => {
+                self.use_skill();
+                return true;
+            }
+            // The log scrolls from any screen, without hurrying the enemy along
+            Message::LogUp => {
+                if self.log_scroll == 0 {
+                    self.log_seen = LOG.lock().unwrap().written();
                 }
+                self.log_scroll = self.log_scroll.saturating_add(LOG_PAGE);
+                return true;
+            }
+            Message::LogDown | Message::LogEnd => {
+                let scroll = self.log_scroll;
+                self.log_scroll = match message {
+                    Message::LogDown => scroll.saturating_sub(LOG_PAGE),
+                    _ => 0,
+                };
+                return had_notice || self.log_scroll != scroll;
+            }
+            Message::Help if matches!(self.current_screen, CurrentScreen::Help) => {
+                self.current_screen = self.previous_screen.pop().unwrap_or(CurrentScreen::Main);
+                return true;
+            }
+            Message::Help if matches!(self.game_state, GameState::Combat) => {
+                self.previous_screen.push(self.current_screen);
+                self.current_screen = CurrentScreen::Help;
+                self.help_scroll = 0;
+                return true;
+            }
+            Message::NextEncounter => {
+                self.leave_combat();
+                self.start_combat(&encounters::ENCOUNTERS[self.next_encounter]);
+                self.next_encounter = (self.next_encounter + 1) % encounters::ENCOUNTERS.len();
+                return true;
+            }
+            // Any other key hurries the enemy along, and the next tick shows what it did
+            _ if matches!(self.current_screen, CurrentScreen::Enemy) => {
+                self.enemy_acts_at = Some(Instant::now());
+                return had_notice;
             }
             Message::Cancel => {
-                self.current_screen = self.previous_screen.pop().unwrap_or(CurrentScreen::Main)
+                let screen = self.current_screen;
+                self.current_screen = self.previous_screen.pop().unwrap_or(CurrentScreen::Main);
+                return had_notice || self.current_screen != screen;
             }
             _ => (),
         }
 
+        // Keys a screen has no use for leave it as it was
+        let mut changed = true;
+
         match self.game_state {
             GameState::Combat => match self.current_screen {
                 CurrentScreen::Main => match message {
@@ -421,17 +1375,67 @@ impl App {
                     }
                     Message::Select => {
                         if let Some(selected) = self.action_list_state.selected() {
-                            let next_screen = self.action_list_items[selected].action;
-                            if matches!(next_screen, CurrentScreen::Target) {
-                                self.start_targeting(&skills::common::BASIC_ATTACK);
-                                // self.start_targeting(&skills::STATIC_DISCHARGE);
-                            } else {
-                                self.previous_screen.push(self.current_screen);
-                                self.current_screen = next_screen;
+                            match self.action_list_items[selected].action {
+                                Action::Melee => {
+                                    self.start_targeting(&skills::common::BASIC_ATTACK);
+                                }
+                                Action::Defend => self.defend(),
+                                Action::Move => self.change_row(),
+                                Action::Flee if !self.can_flee => {
+                                    self.notice = Some("Can't flee from this fight");
+                                }
+                                Action::Flee => {
+                                    self.confirmation = Some(Confirmation {
+                                        title: "Flee?",
+                                        body: format!(
+                                            "{:.0}% chance to escape. Failing costs the turn.",
+                                            self.flee_chance() * 100.
+                                        ),
+                                        on_confirm: Message::Flee,
+                                        on_cancel: None,
+                                        confirm_with: None,
+                                    });
+                                }
+                                Action::Skill
+                                    if !self
+                                        .world
+                                        .get::<&Skills>(self.turn.unwrap())
+                                        .is_ok_and(|skills| !skills.0.is_empty()) =>
+                                {
+                                    self.notice = Some("No skills learned");
+                                }
+                                Action::Skill => {
+                                    // Each actor has their own list, so don't carry the cursor over
+                                    let turn = self.turn.unwrap();
+                                    let suggested = skills::suggested_skill(&self.world, turn)
+                                        .and_then(|suggested| {
+                                            self.world
+                                                .get::<&Skills>(turn)
+                                                .ok()?
+                                                .0
+                                                .iter()
+                                                .position(|skill| skill.name == suggested.name)
+                                        });
+                                    match suggested {
+                                        Some(i) => self.skill_list_state.select(Some(i)),
+                                        None => self.skill_list_state.select_first(),
+                                    }
+                                    self.previous_screen.push(self.current_screen);
+                                    self.current_screen = CurrentScreen::Skill;
+                                }
+                                Action::Item => {
+                                    if self.consumable_list_state.selected().is_none()
+                                        && !self.consumables.is_empty()
+                                    {
+                                        self.consumable_list_state.select_first();
+                                    }
+                                    self.previous_screen.push(self.current_screen);
+                                    self.current_screen = CurrentScreen::Item;
+                                }
                             }
                         }
                     }
-                    _ => (),
+                    _ => changed = false,
                 },
                 CurrentScreen::Skill => {
                     let Skills(skills) = self
@@ -451,19 +1455,30 @@ impl App {
                             }
                         }
                         Message::Down => {
-                            if self.skill_list_state.selected() == Some(skills.len() - 1) {
+                            if self.skill_list_state.selected()
+                                == Some(skills.len().saturating_sub(1))
+                            {
                                 self.skill_list_state.select_first();
                             } else {
                                 self.skill_list_state.select_next();
                             }
                         }
                         Message::Select => {
-                            if let Some(selected) = self.skill_list_state.selected() {
-                                let skill = skills[selected];
-                                self.start_targeting(skill);
+                            if let Some(&skill) = self
+                                .skill_list_state
+                                .selected()
+                                .and_then(|selected| skills.get(selected))
+                            {
+                                if skill.cooldown_left(&self.world, self.turn.unwrap()) > 0 {
+                                    self.notice = Some("Skill is on cooldown");
+                                } else if skill.can_afford(&self.world, self.turn.unwrap()) {
+                                    self.start_targeting(skill);
+                                } else {
+                                    self.notice = Some("Not enough resources");
+                                }
                             }
                         }
-                        _ => (),
+                        _ => changed = false,
                     }
                 }
                 CurrentScreen::Item => match message {
@@ -475,7 +1490,8 @@ impl App {
                         }
                     }
                     Message::Down => {
-                        if self.consumable_list_state.selected() == Some(self.consumables.len() - 1)
+                        if self.consumable_list_state.selected()
+                            == Some(self.consumables.len().saturating_sub(1))
                         {
                             self.consumable_list_state.select_first();
                         } else {
@@ -483,83 +1499,347 @@ impl App {
                         }
                     }
                     Message::Select => {
-                        if let Some(selected) = self.consumable_list_state.selected() {
-                            let skill = self.consumables[selected].skill;
-                            self.start_targeting(skill);
+                        if let Some(item) = self
+                            .consumable_list_state
+                            .selected()
+                            .and_then(|selected| self.consumables.get(selected))
+                        {
+                            self.start_targeting(item.skill);
                         }
                     }
-                    _ => (),
+                    _ => changed = false,
                 },
                 CurrentScreen::Target => match message {
-                    Message::Up | Message::Left => {
-                        if let Some(selected) = &mut self.selected_target {
-                            *selected = (self.targets.len() + *selected - 1) % self.targets.len();
-                        }
-                    }
-                    Message::Down | Message::Right => {
-                        if let Some(selected) = &mut self.selected_target {
-                            *selected = (*selected + 1) % self.targets.len();
-                        }
-                    }
-                    Message::Select => {
-                        self.apply_skill();
-                        if matches!(self.previous_screen.last(), Some(CurrentScreen::Item)) {
-                            self.drain_item();
-                        }
-                        self.finish_turn();
-                        if let Some(turn) = self.turn
-                            && self.world.satisfies::<&Hostile>(turn).unwrap()
-                        {
-                            self.current_screen = CurrentScreen::Enemy;
-                            return Some(Message::Think);
-                        }
-                    }
-                    _ => (),
+                    Message::Left => self.step_target(false),
+                    Message::Right => self.step_target(true),
+                    // Up to the enemies and down to the party, for skills that can hit either
+                    Message::Up => self.switch_target_side(true),
+                    Message::Down => self.switch_target_side(false),
+                    Message::Select => self.confirm_target(),
+                    _ => changed = false,
                 },
-                CurrentScreen::Enemy => match message {
-                    Message::Think => {
-                        sleep(Duration::from_secs(1));
-                        self.think();
-                        self.finish_turn();
-                        if let Some(turn) = self.turn
-                            && self.world.satisfies::<&Hostile>(turn).unwrap()
-                        {
-                            self.current_screen = CurrentScreen::Enemy;
-                            return Some(Message::Think);
-                        }
-                    }
-                    _ => (),
+                CurrentScreen::Help => match message {
+                    Message::Up => self.help_scroll = self.help_scroll.saturating_sub(1),
+                    Message::Down => self.help_scroll = self.help_scroll.saturating_add(1),
+                    _ => changed = false,
                 },
-                _ => (),
+                _ => changed = false,
             },
-            _ => (),
+            GameState::GameOver => match message {
+                Message::Up | Message::Down => {
+                    let selected = self.game_over_list_state.selected().unwrap_or(0);
+                    self.game_over_list_state
+                        .select(Some((selected + 1) % GAME_OVER_OPTIONS.len()));
+                }
+                Message::Select => match self.game_over_list_state.selected() {
+                    Some(0) => self.retry_encounter(),
+                    _ => self.exiting = true,
+                },
+                _ => changed = false,
+            },
+            GameState::Victory => {
+                if let Message::Select = message {
+                    // TODO: Until the overworld is implemented, just restart combat
+                    let encounter = self.random_encounter();
+                    self.start_combat(encounter);
+                } else {
+                    changed = false;
+                }
+            }
+            _ => changed = false,
+        }
+        had_notice || changed
+    }
+
+    /// The newest number floating over the entity, if any
+    pub fn popup(&self, entity: Entity) -> Option<&Popup> {
+        self.popups
+            .iter()
+            .rev()
+            .find(|popup| popup.target == entity)
+    }
+
+    /// The color the entity's card is lit up in, if it was just struck or healed
+    pub fn flash(&self, entity: Entity) -> Option<Color> {
+        self.flashes.get(&entity).map(|flash| flash.color)
+    }
+
+    /// The entity's health bar, while it's still catching up to a change
+    pub fn health_shift(&self, entity: Entity) -> Option<HealthShift> {
+        self.health_shifts
+            .get(&entity)
+            .filter(|shift| !shift.done())
+            .copied()
+    }
+
+    /// Starts a health bar moving for everyone whose health changed since the last tick
+    fn track_health(&mut self) {
+        let now = Instant::now();
+        for (entity, &Health(health)) in self.world.query::<&Health>().iter() {
+            match self.health_shifts.get(&entity) {
+                Some(shift) if shift.to == health => {}
+                // Picks up from wherever the bar got to, if it was still moving
+                Some(shift) => {
+                    let from = shift.shown();
+                    self.health_shifts.insert(
+                        entity,
+                        HealthShift {
+                            from,
+                            to: health,
+                            started: now,
+                        },
+                    );
+                }
+                // New arrivals show up at their health
+                None => {
+                    self.health_shifts.insert(
+                        entity,
+                        HealthShift {
+                            from: health,
+                            to: health,
+                            started: now - HEALTH_SHIFT_DURATION,
+                        },
+                    );
+                }
+            }
+        }
+        self.health_shifts
+            .retain(|&entity, _| self.world.contains(entity));
+    }
+
+    /// Whether anything on screen is moving on its own
+    fn animating(&self) -> bool {
+        !self.popups.is_empty()
+            || !self.flashes.is_empty()
+            || self.health_shifts.values().any(|shift| !shift.done())
+    }
+
+    /// Clears expired banners and popups, moves health bars along, and lets the enemy act once its delay
+    /// is up. Called regularly by the run loop. Returns whether anything visible changed.
+    pub fn tick(&mut self) -> bool {
+        self.track_health();
+        let mut changed = false;
+        if self
+            .banner
+            .as_ref()
+            .is_some_and(|banner| Instant::now() >= banner.until)
+        {
+            self.banner = None;
+            changed = true;
+        }
+        self.popups
+            .retain(|popup| popup.shown_at.elapsed() < POPUP_DURATION);
+        self.flashes
+            .retain(|_, flash| flash.shown_at.elapsed() < FLASH_DURATION);
+        let animating = self.animating();
+        changed |= animating || self.was_animating;
+        self.was_animating = animating;
+        if matches!(self.game_state, GameState::Combat) {
+            let clock = self.combat_started.elapsed().as_secs();
+            changed |= clock != self.clock;
+            self.clock = clock;
         }
-        None
+        // The enemy waits on an open question like the player does
+        if self.confirmation.is_some()
+            || !matches!(self.current_screen, CurrentScreen::Enemy)
+            || self
+                .enemy_acts_at
+                .is_none_or(|acts_at| Instant::now() < acts_at)
+        {
+            return changed;
+        }
+        self.enemy_acts_at = None;
+        self.think();
+        self.end_turn();
+        true
     }
 
     fn think(&mut self) {
-        self.skill = Some(&skills::common::BASIC_ATTACK);
-        self.targets = self
-            .world
-            .query::<&Party>()
+        let turn = self.turn.unwrap();
+        match ai::decide(&self.world, turn, &mut self.rng) {
+            Decision::Cast {
+                skill,
+                targets,
+                weights,
+            } => {
+                if cfg!(debug_assertions) && !weights.is_empty() {
+                    let weights = weights
+                        .iter()
+                        .map(|&(entity, weight)| {
+                            format!("{} {weight:.2}", events::display_name(&self.world, entity))
+                        })
+                        .collect::<Vec<_>>();
+                    LOG.lock().unwrap().write(
+                        Line::from(format!("Target weights: {}", weights.join(", ")))
+                            .dark_gray()
+                            .right_aligned(),
+                    );
+                }
+                self.skill = Some(skill);
+                self.selected_target = (targets.len() == 1).then_some(0);
+                self.targets = targets;
+                self.apply_skill();
+            }
+            Decision::Flee => {
+                LOG.lock().unwrap().write(Line::from(vec![
+                    name_span(&self.world, turn),
+                    " flees!".into(),
+                ]));
+                self.world.despawn(turn).unwrap();
+                self.refresh_next_up();
+            }
+        }
+    }
+
+    pub fn is_confused(&self, entity: Entity) -> bool {
+        self.world.satisfies::<&Confused>(entity).unwrap_or(false)
+    }
+
+    fn living_combatants(&self) -> Vec<Entity> {
+        self.world
+            .query::<Without<&Health, &Dead>>()
             .iter()
             .map(|(e, _)| e)
-            .collect::<Vec<_>>();
-        let mut rng = rand::rng();
-        self.selected_target = Some(rng.random_range(..self.targets.len()));
-        self.apply_skill();
+            .collect()
     }
 
     fn apply_skill(&mut self) {
         let Some(skill) = self.skill else {
             return;
         };
+        let caster = self.turn.unwrap();
+        if self.selected_target.is_some() && self.targets != [caster] && self.is_confused(caster) {
+            // Confusion rerolls the target from everyone still standing, caster included
+            self.targets = self.living_combatants();
+            self.selected_target = Some(self.rng.random_range(..self.targets.len()));
+        }
         let targets = match self.selected_target {
             None => &self.targets,
             Some(selected) => &vec![self.targets[selected]],
         };
-        skill.apply(&mut self.world, self.turn.unwrap(), targets);
+        if skill.charged() && !self.world.satisfies::<&Charging>(caster).unwrap_or(false) {
+            let targets = targets.clone();
+            self.world
+                .insert_one(caster, Charging { skill, targets })
+                .unwrap();
+            self.log_events(&[CombatEvent::ChargeStarted {
+                caster,
+                skill: skill.name,
+            }]);
+            return;
+        }
+        let events = skill.apply(&mut self.world, self.turn.unwrap(), targets, &mut self.rng);
+        self.log_events(&events);
+        self.weakness_struck |= events.iter().any(|event| {
+            matches!(event, CombatEvent::DamageDealt { resistance, .. } if *resistance > 1.)
+        });
+        self.follow_up(&events);
+        self.interrupt_charges(&events);
         self.check_dead();
+        self.advance_phases();
+    }
+
+    /// Sets off the readied follow-ups of the actor's allies. Follow-ups don't set off others.
+    fn follow_up(&mut self, events: &[CombatEvent]) {
+        let actor = self.turn.unwrap();
+        let hostile = self.world.satisfies::<&Hostile>(actor).unwrap_or(false);
+        for event in events {
+            let readied = self
+                .world
+                .query::<Without<(&Readied, Satisfies<&Hostile>), &Dead>>()
+                .iter()
+                .filter(|&(entity, (_, other_hostile))| entity != actor && other_hostile == hostile)
+                .filter_map(|(entity, (readied, _))| {
+                    (readied.trigger)(event).map(|target| (entity, readied.skill, target))
+                })
+                .collect::<Vec<_>>();
+            for (entity, skill, target) in readied {
+                if !self
+                    .world
+                    .get::<&Health>(target)
+                    .is_ok_and(|health| health.0 > 0)
+                {
+                    continue;
+                }
+                self.world.remove_one::<Readied>(entity).unwrap();
+                let mut follow_up = vec![CombatEvent::FollowUp {
+                    caster: entity,
+                    target,
+                }];
+                follow_up.extend(skill.apply(
+                    &mut self.world,
+                    entity,
+                    &vec![target],
+                    &mut self.rng,
+                ));
+                self.log_events(&follow_up);
+            }
+        }
+    }
+
+    /// Writes the events to the log and floats their numbers over whoever took them
+    fn log_events(&mut self, events: &[CombatEvent]) {
+        self.fresh_statuses
+            .extend(events.iter().filter_map(|event| match event {
+                CombatEvent::BuffApplied { target, buff, .. } if Some(*target) == self.turn => {
+                    buff.status_name()
+                }
+                CombatEvent::DebuffApplied { target, debuff, .. } if Some(*target) == self.turn => {
+                    Some(debuff.status_name())
+                }
+                _ => None,
+            }));
+        let mut log = LOG.lock().unwrap();
+        for line in events.iter().filter_map(|event| event.line(&self.world)) {
+            log.write(line);
+        }
+        let shown_at = Instant::now();
+        self.popups
+            .extend(events.iter().filter_map(|event| match *event {
+                CombatEvent::DamageDealt {
+                    target,
+                    amount,
+                    crit,
+                    ..
+                } if amount > 0 => Some(Popup {
+                    target,
+                    text: if crit {
+                        format!("{amount}!").bold().yellow()
+                    } else {
+                        amount.to_string().bold().red()
+                    },
+                    shown_at,
+                }),
+                CombatEvent::Healed { target, amount } if amount > 0 => Some(Popup {
+                    target,
+                    text: format!("+{amount}").bold().green(),
+                    shown_at,
+                }),
+                _ => None,
+            }));
+        self.flashes
+            .extend(events.iter().filter_map(|event| match *event {
+                CombatEvent::DamageDealt {
+                    target,
+                    amount,
+                    crit,
+                    ..
+                } if amount > 0 => Some((
+                    target,
+                    Flash {
+                        color: if crit { Color::LightYellow } else { Color::Red },
+                        shown_at,
+                    },
+                )),
+                CombatEvent::Healed { target, amount } if amount > 0 => Some((
+                    target,
+                    Flash {
+                        color: Color::Green,
+                        shown_at,
+                    },
+                )),
+                _ => None,
+            }));
     }
 
     fn drain_item(&mut self) {
@@ -568,6 +1848,12 @@ impl App {
             item.amount = item.amount.saturating_sub(1);
             if item.amount == 0 {
                 self.consumables.remove(selected);
+                // Keep the cursor on a row that still exists
+                if self.consumables.is_empty() {
+                    self.consumable_list_state.select(None);
+                } else if selected >= self.consumables.len() {
+                    self.consumable_list_state.select_last();
+                }
             }
         }
     }
@@ -581,67 +1867,778 @@ impl App {
             .collect::<Vec<_>>();
         dead.iter().for_each(|&entity| {
             if self.world.satisfies::<&Party>(entity).unwrap() {
-                self.world.insert_one(entity, Dead).unwrap()
+                self.world.insert_one(entity, Dead).unwrap();
+                // Reviving shouldn't bring back a half-cast skill
+                let _ = self.world.remove_one::<Charging>(entity);
             } else {
+                if self.world.satisfies::<&Hostile>(entity).unwrap() {
+                    self.defeat(entity);
+                }
                 self.world.despawn(entity).unwrap();
             }
         });
+        let ghosts = self
+            .world
+            .query::<With<(), &Ghost>>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for ghost in ghosts {
+            self.world.despawn(ghost).unwrap();
+        }
         self.refresh_next_up();
     }
 
+    /// Records a defeated enemy in the summary and rolls for its drop.
+    fn defeat(&mut self, enemy: Entity) {
+        let name = self.world.get::<&Name>(enemy).unwrap().0;
+        self.summary.defeated.push(name);
+        self.summary.xp_pool += self.world.get::<&XpReward>(enemy).map_or(0, |xp| xp.0);
+        if self.rng.random_bool(LOOT_CHANCE)
+            && let Some(&(item, skill)) = LOOT_TABLE.choose(&mut self.rng)
+        {
+            self.summary.loot.push(item);
+            match self.consumables.iter_mut().find(|owned| owned.name == item) {
+                Some(owned) => owned.amount += 1,
+                None => self.consumables.push(Consumable {
+                    name: item,
+                    amount: 1,
+                    skill,
+                }),
+            }
+        }
+    }
+
+    /// Moves bosses on to the phase their health has dropped to, running the transition of every
+    /// phase crossed on the way.
+    fn advance_phases(&mut self) {
+        let crossed = self
+            .world
+            .query::<(&Boss, &Health, &Stats)>()
+            .iter()
+            .flat_map(|(entity, (boss, &Health(health), stats))| {
+                let fraction = health as f32 / stats.max_health as f32;
+                let reached = boss
+                    .phases
+                    .iter()
+                    .rposition(|phase| fraction <= phase.threshold)
+                    .unwrap_or(0);
+                (boss.phase + 1..=reached).map(move |phase| (entity, phase))
+            })
+            .collect::<Vec<_>>();
+        for (entity, phase) in crossed {
+            let (ai, skills, announcement, transition) = {
+                let mut boss = self.world.get::<&mut Boss>(entity).unwrap();
+                boss.phase = phase;
+                let phase = &boss.phases[phase];
+                (
+                    phase.ai,
+                    phase.skills.clone(),
+                    phase.announcement,
+                    phase.transition,
+                )
+            };
+            self.world.insert(entity, (ai, Skills(skills))).unwrap();
+            LOG.lock().unwrap().write(Line::from(vec![
+                name_span(&self.world, entity).bold(),
+                announcement.bold().light_red(),
+            ]));
+            if let Some(transition) = transition {
+                let events =
+                    transition.apply(&mut self.world, entity, &vec![entity], &mut self.rng);
+                self.log_events(&events);
+            }
+        }
+        self.refresh_next_up();
+    }
+
+    fn party_wiped(&self) -> bool {
+        self.world
+            .query::<Without<With<(), &Party>, &Dead>>()
+            .iter()
+            .next()
+            .is_none()
+    }
+
+    fn round_over(&self) -> bool {
+        self.world
+            .query::<Without<With<(), &Initiative>, &Dead>>()
+            .iter()
+            .all(|(entity, _)| self.acted.contains(&entity))
+    }
+
+    /// Starts the next round. Effects that go by rounds rather than turns go here.
+    fn end_round(&mut self) {
+        self.round_number += 1;
+        self.acted.clear();
+        self.clear_reactions();
+        LOG.lock().unwrap().write(
+            Line::from(format!("── Round {} ──", self.round_number))
+                .dark_gray()
+                .centered(),
+        );
+        self.call_reinforcements();
+    }
+
+    /// Brings in the encounter's next waves once they're due. A wave also comes in early if
+    /// everyone before it is down, so the fight isn't won until every wave is beaten.
+    fn call_reinforcements(&mut self) {
+        let Some(encounter) = self.encounter else {
+            return;
+        };
+        let mut arrived = false;
+        while let Some(wave) = encounter.waves.get(self.next_wave) {
+            let standing = self.world.query::<With<(), &Hostile>>().iter().count();
+            let due = standing == 0
+                || match wave.trigger {
+                    WaveTrigger::Round(round) => self.round_number >= round,
+                    WaveTrigger::FewerThan(count) => standing < count,
+                };
+            if !due {
+                break;
+            }
+            self.next_wave += 1;
+            arrived = true;
+
+            // Reinforcements get their first turn once whoever is up now has had theirs
+            let now = self
+                .world
+                .query::<Without<&Initiative, &Dead>>()
+                .iter()
+                .map(|(_, initiative)| initiative.0)
+                .fold(f32::INFINITY, f32::min);
+            for enemy in encounters::spawn_templates(&mut self.world, wave.enemies, &mut self.rng) {
+                let speed = effective_speed(&self.world, enemy);
+                if let Ok(mut initiative) = self.world.get::<&mut Initiative>(enemy) {
+                    initiative.0 = now + 1. / speed as f32;
+                }
+            }
+        }
+        if arrived {
+            LOG.lock()
+                .unwrap()
+                .write(Line::from("Reinforcements arrive!".bold().light_red()));
+            self.banner = Some(Banner {
+                text: "Reinforcements!".bold().light_red(),
+                until: Instant::now() + BANNER_DURATION,
+            });
+            self.refresh_next_up();
+        }
+    }
+
     fn finish_turn(&mut self) {
+        if self.party_wiped() {
+            self.game_over();
+            return;
+        }
+        self.call_reinforcements();
+        if self.world.query::<With<(), &Hostile>>().iter().count() == 0 {
+            self.end_combat();
+            return;
+        }
+        // Striking a weakness grants one more action, but a bonus turn can't earn another
+        if std::mem::take(&mut self.weakness_struck) && !self.bonus_turn {
+            self.bonus_turn = true;
+            let mut log = LOG.lock().unwrap();
+            log.write(Line::from(vec![
+                name_span(&self.world, self.turn.unwrap()).bold(),
+                " gets another turn!".bold(),
+            ]));
+            self.current_screen = CurrentScreen::Main;
+            self.previous_screen.clear();
+            return;
+        }
+        self.bonus_turn = false;
+
+        let mut events = Vec::new();
+        skills::burn(&mut self.world, self.turn.unwrap(), &mut events);
+        skills::tick_pending(
+            &mut self.world,
+            self.turn.unwrap(),
+            &mut self.rng,
+            &mut events,
+        );
+        self.log_events(&events);
+        self.check_dead();
+        self.advance_phases();
+        if self.party_wiped() {
+            self.game_over();
+            return;
+        }
+        self.call_reinforcements();
         if self.world.query::<With<(), &Hostile>>().iter().count() == 0 {
             self.end_combat();
             return;
         }
         {
-            let query = self
-                .world
-                .query_one::<(&mut Initiative, &Stats)>(self.turn.unwrap());
+            let speed = effective_speed(&self.world, self.turn.unwrap());
             // Entity may have died during its turn so we can't unwrap the Result here.
-            if let Ok(mut query) = query
-                && let Some((Initiative(initiative), stats)) = query.get()
-            {
-                *initiative += 1. / stats.speed as f32;
+            if let Ok(mut initiative) = self.world.get::<&mut Initiative>(self.turn.unwrap()) {
+                initiative.0 += 1. / speed as f32;
             }
         }
+        // Tick after advancing initiative so the last turn of a status still counts. Statuses
+        // picked up this turn haven't had a turn to count yet.
+        let fresh = std::mem::take(&mut self.fresh_statuses);
+        let mut events = Vec::new();
+        tick_statuses(&mut self.world, self.turn.unwrap(), &fresh, &mut events);
+        self.log_events(&events);
+        tick_cooldowns(&mut self.world, self.turn.unwrap());
+        dissipate_heat(&mut self.world, self.turn.unwrap());
+        decay_threat(&mut self.world, self.turn.unwrap());
+        skills::tick_resources(&mut self.world, self.turn.unwrap());
+        self.turn_number += 1;
+        self.acted.insert(self.turn.unwrap());
+        if self.round_over() {
+            self.end_round();
+        }
         self.refresh_next_up();
         if let Some(next_up) = &self.next_up {
             self.turn = next_up.0.peek().map(|i| i.entity);
         }
         self.current_screen = CurrentScreen::Main;
         self.previous_screen.clear();
+        if let Some(turn) = self.turn {
+            let _ = self.world.remove_one::<Defending>(turn);
+        }
+        self.announce_turn();
+
+        // Only checked once the turn has passed, so a stun applied mid-turn doesn't cancel the
+        // current actor. The skipped turn still ticks its statuses down.
+        if let Some(turn) = self.turn
+            && self.loses_turn(turn)
+        {
+            self.finish_turn();
+        } else if let Some(turn) = self.turn
+            && self.world.satisfies::<&Charging>(turn).unwrap_or(false)
+        {
+            // Charged skills go off in place of the action menu
+            self.release_charge(turn);
+            self.finish_turn();
+        }
+    }
+
+    fn release_charge(&mut self, caster: Entity) {
+        let (skill, targets) = {
+            let charging = self.world.get::<&Charging>(caster).unwrap();
+            (charging.skill, charging.targets.clone())
+        };
+        // Targets may have died while the skill was charging
+        let mut targets = targets
+            .into_iter()
+            .filter(|&target| {
+                !self.world.satisfies::<&Dead>(target).unwrap_or(true)
+                    && self
+                        .world
+                        .get::<&Health>(target)
+                        .is_ok_and(|health| health.0 > 0)
+            })
+            .collect::<Vec<_>>();
+        if targets.is_empty() {
+            let (fresh, many) = skill.get_targets(&self.world, caster);
+            targets = if many {
+                fresh
+            } else {
+                fresh.into_iter().take(1).collect()
+            };
+        }
+        if targets.is_empty() {
+            self.world.remove_one::<Charging>(caster).unwrap();
+            self.log_events(&[CombatEvent::ChargeFizzled {
+                caster,
+                skill: skill.name,
+            }]);
+            return;
+        }
+        self.skill = Some(skill);
+        self.targets = targets;
+        self.selected_target = None;
+        self.apply_skill();
+        // The caster may have been despawned by its own skill
+        let _ = self.world.remove_one::<Charging>(caster);
+    }
+
+    /// Big hits and stuns may break the concentration of anyone charging a skill
+    fn interrupt_charges(&mut self, events: &[CombatEvent]) {
+        for event in events {
+            let target = match event {
+                CombatEvent::DamageDealt { target, amount, .. } => {
+                    let max_health = self
+                        .world
+                        .get::<&Stats>(*target)
+                        .map_or(u32::MAX, |stats| stats.max_health);
+                    if *amount < max_health / 4 {
+                        continue;
+                    }
+                    *target
+                }
+                CombatEvent::DebuffApplied {
+                    target,
+                    debuff: skills::Debuff::Stunned { .. },
+                    ..
+                } => *target,
+                _ => continue,
+            };
+            let Ok(skill) = self
+                .world
+                .get::<&Charging>(target)
+                .map(|charging| charging.skill)
+            else {
+                continue;
+            };
+            if self.rng.random_bool(INTERRUPT_CHANCE) {
+                self.world.remove_one::<Charging>(target).unwrap();
+                self.log_events(&[CombatEvent::ChargeInterrupted {
+                    caster: target,
+                    skill: skill.name,
+                }]);
+            }
+        }
+    }
+
+    fn loses_turn(&self, entity: Entity) -> bool {
+        if let Ok(frozen) = self.world.get::<&Frozen>(entity)
+            && frozen.0 >= FROZEN_SOLID
+        {
+            let mut log = LOG.lock().unwrap();
+            log.write(Line::from(vec![
+                name_span(&self.world, entity),
+                " is frozen solid".into(),
+            ]));
+            return true;
+        }
+        if self.world.satisfies::<&Stunned>(entity).unwrap_or(false) {
+            let mut log = LOG.lock().unwrap();
+            log.write(Line::from(vec![
+                name_span(&self.world, entity),
+                " is stunned!".into(),
+            ]));
+            return true;
+        }
+        if let Ok(mut job) = self.world.get::<&mut Job>(entity)
+            && let Job::Netrunner { heat, .. } = &mut *job
+            && *heat >= OVERHEAT
+        {
+            *heat = 0;
+            drop(job);
+            let mut log = LOG.lock().unwrap();
+            log.write(Line::from(vec![
+                name_span(&self.world, entity),
+                " overheats! Thermal shutdown".light_red(),
+            ]));
+            return true;
+        }
+        false
     }
 
     fn end_combat(&mut self) {
-        level_up(&mut self.world);
+        // Split between the party, with the fallen getting a smaller share
+        let members = self
+            .world
+            .query_mut::<Without<With<(&Name, &mut Xp, Satisfies<&Dead>), &Party>, &Summoned>>()
+            .into_iter()
+            .map(|(_, (&Name(name), xp, dead))| (name, xp, dead))
+            .collect::<Vec<_>>();
+        let shares = members
+            .iter()
+            .map(|&(_, _, dead)| if dead { DEAD_XP_SHARE } else { 1. })
+            .sum::<f32>();
+        let share = self.summary.xp_pool as f32 / shares;
+        let mut log = LOG.lock().unwrap();
+        for (name, Xp(total), dead) in members {
+            let xp = if dead { share * DEAD_XP_SHARE } else { share }.round() as u32;
+            self.summary.xp.push((name, xp, *total));
+            *total += xp;
+            log.write(Line::from(vec![
+                name.green(),
+                " gains ".into(),
+                format!("{xp} XP").bold(),
+            ]));
+        }
+        drop(log);
+        self.leave_combat();
+        for entity in level_up(&mut self.world) {
+            if let Ok((&Name(name), &Level(level))) =
+                self.world.query_one_mut::<(&Name, &Level)>(entity)
+            {
+                self.summary.level_ups.push((name, level));
+            }
+        }
+        // Shown until dismissed, then it's on to the next fight
+        self.game_state = GameState::Victory;
+    }
+
+    fn game_over(&mut self) {
+        LOG.lock()
+            .unwrap()
+            .write(Line::from("The party has fallen...".bold().light_red()));
+        self.leave_combat();
+        self.game_state = GameState::GameOver;
+        self.game_over_list_state.select_first();
+    }
+
+    /// Puts the party back the way it was when the fight started and starts it over.
+    fn retry_encounter(&mut self) {
+        for snapshot in std::mem::take(&mut self.party_snapshot) {
+            for status in DEBUFFS.iter().chain(BUFFS.iter()) {
+                (status.remove)(&mut self.world, snapshot.entity);
+            }
+            let _ = self.world.remove_one::<Barrier>(snapshot.entity);
+            let _ = self.world.remove_one::<Dead>(snapshot.entity);
+            if snapshot.dead {
+                self.world.insert_one(snapshot.entity, Dead).unwrap();
+            }
+            self.world
+                .insert(snapshot.entity, (Health(snapshot.health), snapshot.job))
+                .unwrap();
+        }
+        if let Some(encounter) = self.encounter {
+            self.start_combat(encounter);
+        }
+    }
+
+    /// Leaves combat without winning, so without any XP or level ups.
+    fn escape_combat(&mut self) {
+        self.leave_combat();
+
+        // TODO: Until the overworld is implemented, just restart combat
+        let encounter = self.random_encounter();
+        self.start_combat(encounter);
+    }
+
+    fn clear_reactions(&mut self) {
+        let reacting = self
+            .world
+            .query::<With<(), &Reaction>>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for entity in reacting {
+            self.world.remove_one::<Reaction>(entity).unwrap();
+        }
+    }
+
+    /// Cleanup shared by every way out of combat, so the next fight starts fresh.
+    fn leave_combat(&mut self) {
+        let summons = self
+            .world
+            .query::<With<(), &Summoned>>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for summon in summons {
+            self.world.despawn(summon).unwrap();
+        }
+        for (_, Cooldowns(cooldowns)) in self.world.query_mut::<&mut Cooldowns>() {
+            cooldowns.clear();
+        }
+        for (_, Threat(threat)) in self.world.query_mut::<&mut Threat>() {
+            *threat = 0.;
+        }
+        let charging = self
+            .world
+            .query::<With<(), &Charging>>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for entity in charging {
+            self.world.remove_one::<Charging>(entity).unwrap();
+        }
+        let defending = self
+            .world
+            .query::<With<(), &Defending>>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for entity in defending {
+            self.world.remove_one::<Defending>(entity).unwrap();
+        }
+        let scanned = self
+            .world
+            .query::<With<(), &Scanned>>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for entity in scanned {
+            self.world.remove_one::<Scanned>(entity).unwrap();
+        }
+        self.clear_reactions();
+        self.turn = None;
+        self.next_up = None;
+        self.targets.clear();
+        self.selected_target = None;
+        self.skill = None;
+        self.weakness_struck = false;
+        self.bonus_turn = false;
+        self.fresh_statuses.clear();
+        self.enemy_acts_at = None;
+        self.banner = None;
+        self.popups.clear();
+        self.flashes.clear();
+        self.health_shifts.clear();
         self.game_state = GameState::Overworld;
         self.current_screen = CurrentScreen::Main;
+        self.previous_screen.clear();
+    }
 
-        // TODO: Until the overworld is implemented, just restart combat
-        self.start_combat(Advantage::Neutral);
+    /// The party's chance to get away from this fight, as worked out by `escape_chance`
+    pub fn flee_chance(&self) -> f32 {
+        escape_chance(
+            &self.world,
+            self.can_flee,
+            self.advantage,
+            self.failed_flees,
+        )
     }
 
-    fn start_targeting(&mut self, skill: &'static Skill) {
-        self.previous_screen.push(self.current_screen);
-        self.current_screen = CurrentScreen::Target;
+    fn defend(&mut self) {
+        let turn = self.turn.unwrap();
+        self.world.insert_one(turn, Defending).unwrap();
+        let speed = effective_speed(&self.world, turn);
+        if let Ok(mut initiative) = self.world.get::<&mut Initiative>(turn) {
+            initiative.0 -= DEFEND_REBATE / speed as f32;
+        }
+        LOG.lock().unwrap().write(Line::from(vec![
+            name_span(&self.world, turn),
+            " defends".into(),
+        ]));
+        self.end_turn();
+    }
+
+    /// Swaps between the front and back row, taking up the turn
+    fn change_row(&mut self) {
+        let turn = self.turn.unwrap();
+        let row = {
+            let mut row = self.world.get::<&mut Row>(turn).unwrap();
+            *row = match *row {
+                Row::Front => Row::Back,
+                Row::Back => Row::Front,
+            };
+            *row
+        };
+        LOG.lock().unwrap().write(Line::from(vec![
+            name_span(&self.world, turn),
+            match row {
+                Row::Front => " moves to the front row",
+                Row::Back => " moves to the back row",
+            }
+            .into(),
+        ]));
+        self.end_turn();
+    }
 
+    fn flee(&mut self) {
+        if !self.can_flee {
+            self.notice = Some("Can't flee from this fight");
+            return;
+        }
+        let turn = self.turn.unwrap();
+        if self.rng.random_bool(self.flee_chance() as f64) {
+            LOG.lock()
+                .unwrap()
+                .write(Line::from("The party escapes!".bold()));
+            self.escape_combat();
+            return;
+        }
+        LOG.lock().unwrap().write(Line::from(vec![
+            name_span(&self.world, turn),
+            " fails to escape!".into(),
+        ]));
+        self.failed_flees += 1;
+        self.end_turn();
+    }
+
+    fn start_targeting(&mut self, skill: &'static Skill) {
         let (targets, many) = skill.get_targets(
             &self.world,
             self.turn
                 .expect("Can't get here unless it's someone's turn"),
         );
-        self.targets = targets;
+        if targets.is_empty() {
+            // Stay on the current screen rather than wasting the turn or item
+            self.notice = Some("No valid targets");
+            return;
+        }
+
+        self.previous_screen.push(self.current_screen);
+        self.current_screen = CurrentScreen::Target;
         self.selected_target = (!many).then_some(0);
+        self.targets = targets;
+        self.targets
+            .sort_by_key(|&target| field_position(&self.world, target));
         self.skill = Some(skill);
+
+        // Nothing to choose for self-targeted skills
+        if skill.targets_caster() {
+            self.confirm_target();
+        }
+    }
+
+    /// Moves the selection to the next target on the same side of the field, wrapping around.
+    fn step_target(&mut self, forward: bool) {
+        let Some(selected) = self.selected_target else {
+            return;
+        };
+        let hostile = |target: Entity| self.world.satisfies::<&Hostile>(target).unwrap_or(false);
+        let side = hostile(self.targets[selected]);
+        let row = (0..self.targets.len())
+            .filter(|&i| hostile(self.targets[i]) == side)
+            .collect::<Vec<_>>();
+        let at = row.iter().position(|&i| i == selected).unwrap_or(0);
+        let next = if forward {
+            (at + 1) % row.len()
+        } else {
+            (at + row.len() - 1) % row.len()
+        };
+        self.selected_target = Some(row[next]);
+    }
+
+    /// Jumps the selection over to the other side of the field, or steps along the side it's on
+    /// if it's already there or there's nobody to jump to.
+    fn switch_target_side(&mut self, to_enemies: bool) {
+        let Some(selected) = self.selected_target else {
+            return;
+        };
+        let hostile = |target: Entity| self.world.satisfies::<&Hostile>(target).unwrap_or(false);
+        if hostile(self.targets[selected]) != to_enemies
+            && let Some(first) = self
+                .targets
+                .iter()
+                .position(|&target| hostile(target) == to_enemies)
+        {
+            self.selected_target = Some(first);
+        } else {
+            self.step_target(!to_enemies);
+        }
+    }
+
+    /// Passes the turn on, handing over to the enemy if it's their turn next.
+    fn end_turn(&mut self) {
+        self.finish_turn();
+        self.queue_enemy_turn();
+    }
+
+    /// Marks the start of a turn in the log, so each actor's doings are easy to pick out
+    fn announce_turn(&self) {
+        let Some(turn) = self.turn else {
+            return;
+        };
+        LOG.lock().unwrap().write(
+            Line::from(vec![
+                format!("─── Round {} · ", self.round_number).dark_gray(),
+                name_span(&self.world, turn).dim(),
+                " ───".dark_gray(),
+            ])
+            .centered(),
+        );
+    }
+
+    /// Hands over to the enemy if it's their turn, giving the player a moment to follow along.
+    fn queue_enemy_turn(&mut self) {
+        if let Some(turn) = self.turn
+            && self.world.satisfies::<&Hostile>(turn).unwrap()
+        {
+            self.current_screen = CurrentScreen::Enemy;
+            self.enemy_acts_at = Some(Instant::now() + ENEMY_DELAY);
+        }
+    }
+
+    fn confirm_target(&mut self) {
+        if let Some(skill) = self.skill
+            && let Some(&target) = match self.selected_target {
+                None => self.targets.first(),
+                Some(selected) => self.targets.get(selected),
+            }
+            && !skill.can_afford_against(&self.world, self.turn.unwrap(), target)
+        {
+            self.notice = Some("Not enough resources");
+            return;
+        }
+        if matches!(self.previous_screen.last(), Some(CurrentScreen::Item))
+            && let Some(item) = self
+                .consumable_list_state
+                .selected()
+                .and_then(|selected| self.consumables.get(selected))
+            && item.amount == 1
+        {
+            self.confirmation = Some(Confirmation {
+                title: "Use the last one?",
+                body: format!("This is your last {}.", item.name),
+                on_confirm: Message::UseItem,
+                // Back to the item list
+                on_cancel: Some(Message::Cancel),
+                confirm_with: None,
+            });
+            return;
+        }
+        self.use_skill();
     }
 
-    pub fn start_combat(&mut self, advantage: Advantage) {
+    fn use_skill(&mut self) {
+        self.apply_skill();
+        if matches!(self.previous_screen.last(), Some(CurrentScreen::Item)) {
+            self.drain_item();
+        }
+        self.end_turn();
+    }
+
+    pub fn start_combat(&mut self, encounter: &'static Encounter) {
         self.game_state = GameState::Combat;
+        self.encounter = Some(encounter);
+        self.combat_started = Instant::now();
+        self.next_wave = 0;
+        self.summary = CombatSummary::default();
+        self.turn_number = 0;
+        self.round_number = 1;
+        self.acted.clear();
+        self.party_snapshot = self
+            .world
+            .query::<With<(&Health, &Job, Satisfies<&Dead>), &Party>>()
+            .iter()
+            .map(|(entity, (&Health(health), &job, dead))| PartySnapshot {
+                entity,
+                health,
+                job,
+                dead,
+            })
+            .collect();
+        self.can_flee = encounter.can_flee;
+        self.failed_flees = 0;
         self.current_screen = CurrentScreen::Main;
         self.previous_screen.clear();
 
-        spawn_enemies(&mut self.world);
+        // Whoever is left over from the last fight doesn't get to join this one
+        let leftovers = self
+            .world
+            .query::<With<(), &Hostile>>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        for enemy in leftovers {
+            self.world.despawn(enemy).unwrap();
+        }
+        encounter.spawn(&mut self.world, &mut self.rng);
+        LOG.lock()
+            .unwrap()
+            .write(Line::from(format!("Encounter: {}", encounter.name).bold()));
+
+        let advantage = match encounter.advantage {
+            Advantage::Neutral => roll_advantage(&self.world, &mut self.rng),
+            forced => forced,
+        };
+        self.advantage = advantage;
+        let banner = match advantage {
+            Advantage::Friendly => Some("Ambush!".bold().light_green()),
+            Advantage::Enemy => Some("Ambushed!".bold().light_red()),
+            Advantage::Neutral => None,
+        };
+        if let Some(text) = banner {
+            LOG.lock().unwrap().write(Line::from(text.clone()));
+            self.banner = Some(Banner {
+                text,
+                until: Instant::now() + BANNER_DURATION,
+            });
+        }
 
         for (_, (stats, Initiative(initiative), hostile)) in
             self.world
@@ -658,34 +2655,49 @@ impl App {
         self.turn = self
             .next_up
             .as_ref()
-            .and_then(|nu| nu.0.peek().and_then(|i| Some(i.entity)));
+            .and_then(|nu| nu.0.peek().map(|i| i.entity));
 
         self.action_list_items = &[
             ActionListItem {
                 text: "Skill",
-                action: CurrentScreen::Skill,
+                action: Action::Skill,
             },
             ActionListItem {
                 text: "Melee",
-                action: CurrentScreen::Target,
+                action: Action::Melee,
             },
             ActionListItem {
                 text: "Item",
-                action: CurrentScreen::Item,
+                action: Action::Item,
+            },
+            ActionListItem {
+                text: "Defend",
+                action: Action::Defend,
+            },
+            ActionListItem {
+                text: "Move",
+                action: Action::Move,
+            },
+            ActionListItem {
+                text: "Flee",
+                action: Action::Flee,
             },
         ];
         self.action_list_state.select_first();
+        self.announce_turn();
+        self.queue_enemy_turn();
     }
 
+    /// Rebuilds the turn order. The dead are left out until they're revived.
     fn refresh_next_up(&mut self) {
         self.next_up = Some(NextUp(BinaryHeap::from_iter(
             self.world
-                .query::<(&Initiative, &Stats, Satisfies<&Hostile>)>()
+                .query::<Without<(&Initiative, Satisfies<&Hostile>), &Dead>>()
                 .iter()
                 .map(
-                    |(entity, (&Initiative(initiative), stats, hostile))| InitiativeInfo {
+                    |(entity, (&Initiative(initiative), hostile))| InitiativeInfo {
                         initiative,
-                        speed: stats.speed,
+                        speed: effective_speed(&self.world, entity),
                         hostile,
                         entity,
                     },
@@ -693,3 +2705,333 @@ impl App {
         )));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{PoisonError, RwLock, RwLockReadGuard};
+
+    use super::*;
+
+    /// The log is shared by every test. Fights write to it side by side, while reading it back
+    /// needs it to themselves.
+    static LOG_ACCESS: RwLock<()> = RwLock::new(());
+
+    #[test]
+    fn debuff_expires_on_its_last_turn() {
+        let mut world = World::new();
+        let entity = world.spawn((Stunned(2), Slowed(1)));
+        let mut events = Vec::new();
+
+        tick_statuses(&mut world, entity, &[], &mut events);
+        assert!(world.satisfies::<&Stunned>(entity).unwrap());
+        assert!(!world.satisfies::<&Slowed>(entity).unwrap());
+        assert!(matches!(
+            events[..],
+            [CombatEvent::WoreOff {
+                status: Slowed::NAME,
+                harmful: true,
+                ..
+            }]
+        ));
+
+        events.clear();
+        tick_statuses(&mut world, entity, &[], &mut events);
+        assert!(!world.satisfies::<&Stunned>(entity).unwrap());
+        assert!(matches!(
+            events[..],
+            [CombatEvent::WoreOff {
+                status: Stunned::NAME,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn burning_expires_on_its_last_turn_whatever_its_stacks() {
+        let mut world = World::new();
+        let entity = world.spawn((Burning {
+            stacks: 3,
+            turns: 2,
+        },));
+        let mut events = Vec::new();
+
+        tick_statuses(&mut world, entity, &[], &mut events);
+        assert_eq!(turns_left::<Burning>(&world, entity), Some(1));
+        assert_eq!(stacks::<Burning>(&world, entity), 3);
+        tick_statuses(&mut world, entity, &[], &mut events);
+        assert_eq!(turns_left::<Burning>(&world, entity), None);
+        assert_eq!(events.len(), 1);
+    }
+
+    fn start_fight(seed: u64) -> App {
+        let mut app = App::new(Some(seed), KeyMap::default(), true);
+        app.start_combat(&encounters::ENCOUNTERS[0]);
+        app
+    }
+
+    /// A fight that writes to the log alongside other tests
+    fn fight(seed: u64) -> (RwLockReadGuard<'static, ()>, App) {
+        let log = LOG_ACCESS.read().unwrap_or_else(PoisonError::into_inner);
+        (log, start_fight(seed))
+    }
+
+    #[test]
+    fn status_given_to_self_lasts_past_the_turn() {
+        let (_log, mut app) = fight(1);
+        let actor = app.turn.unwrap();
+        app.skill = Some(&skills::gunslinger::TACTICAL_RELOAD);
+        app.targets = vec![actor];
+        app.selected_target = None;
+        app.apply_skill();
+        app.finish_turn();
+        assert_eq!(turns_left::<Shelled>(&app.world, actor), Some(1));
+    }
+
+    #[test]
+    fn party_summon_shows_up_and_takes_turns() {
+        let (_log, mut app) = fight(1);
+        let (caster, _) = app.world.query::<With<(), &Party>>().iter().next().unwrap();
+        app.turn = Some(caster);
+        app.skill = Some(&skills::technopriest::DEPLOY_TURRET);
+        app.targets = vec![caster];
+        app.selected_target = None;
+        app.apply_skill();
+        let (turret, _) = app
+            .world
+            .query::<With<(), &Summoned>>()
+            .iter()
+            .next()
+            .unwrap();
+        let shown = app
+            .world
+            .query::<With<(&Name, &Health, &Stats, &Job), &Party>>()
+            .iter()
+            .any(|(entity, _)| entity == turret);
+        assert!(shown);
+
+        app.end_turn();
+        while app.turn != Some(turret) {
+            app.think();
+            app.end_turn();
+        }
+        let enemy_health = |app: &App| {
+            app.world
+                .query::<With<&Health, &Hostile>>()
+                .iter()
+                .map(|(_, health)| health.0)
+                .sum::<u32>()
+        };
+        let before = enemy_health(&app);
+        // Whatever it picks has to land to show up in the enemies' health
+        for (_, stats) in app.world.query_mut::<With<&mut Stats, &Hostile>>() {
+            stats.evade = 0.;
+        }
+        app.think();
+        assert!(enemy_health(&app) < before);
+    }
+
+    #[test]
+    fn confused_caster_can_hit_itself() {
+        let (_log, mut app) = fight(1);
+        let actor = app.turn.unwrap();
+        let other = app
+            .living_combatants()
+            .into_iter()
+            .find(|&entity| entity != actor)
+            .unwrap();
+        app.world.insert_one(actor, Confused(1)).unwrap();
+        let hit_itself = (0..50).any(|_| {
+            let before = app.world.get::<&Health>(actor).unwrap().0;
+            app.skill = Some(&skills::common::BASIC_ATTACK);
+            app.targets = vec![other];
+            app.selected_target = Some(0);
+            app.apply_skill();
+            app.targets[app.selected_target.unwrap()] == actor
+                && app.world.get::<&Health>(actor).unwrap().0 < before
+        });
+        assert!(hit_itself);
+    }
+
+    #[test]
+    fn party_wipe_ends_the_game() {
+        let (_log, mut app) = fight(1);
+        let party = app
+            .world
+            .query::<With<(), &Party>>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for &member in &party[1..] {
+            app.world.get::<&mut Health>(member).unwrap().0 = 0;
+        }
+        app.check_dead();
+        app.finish_turn();
+        assert!(matches!(app.game_state, GameState::Combat));
+
+        app.world.get::<&mut Health>(party[0]).unwrap().0 = 0;
+        app.check_dead();
+        app.finish_turn();
+        assert!(matches!(app.game_state, GameState::GameOver));
+    }
+
+    /// Level and XP of a lone 0 XP party member after winning a fight worth that much XP
+    fn win_fight_worth(xp: u32) -> (u8, u32) {
+        let (_log, mut app) = fight(1);
+        let party = app
+            .world
+            .query::<With<(), &Party>>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for &other in &party[1..] {
+            app.world.despawn(other).unwrap();
+        }
+        assert_eq!(app.world.get::<&Xp>(party[0]).unwrap().0, 0);
+        assert_eq!(app.world.get::<&Level>(party[0]).unwrap().0, 1);
+        app.summary.xp_pool = xp;
+        app.end_combat();
+        let level = app.world.get::<&Level>(party[0]).unwrap().0;
+        let xp = app.world.get::<&Xp>(party[0]).unwrap().0;
+        (level, xp)
+    }
+
+    #[test]
+    fn xp_levels_up_at_the_threshold() {
+        assert_eq!(next_level_xp(1), 100);
+        assert_eq!(win_fight_worth(150), (2, 150));
+        assert_eq!(win_fight_worth(99), (1, 99));
+        assert_eq!(win_fight_worth(100), (2, 100));
+    }
+
+    #[test]
+    fn stats_grow_by_job() {
+        let gunslinger = Job::Gunslinger { ammo: 0 };
+        let netrunner = Job::Netrunner { ram: 0, heat: 0 };
+        let technopriest = Job::Technopriest { prayers: 0 };
+        let clairvoyant = Job::Clairvoyant { sun: 0, moon: 0 };
+        let nanovampire = Job::Nanovampire { battery: 0 };
+        // Job, level, then max health, attack, speed and crit
+        let table = [
+            (gunslinger, 1, 98, 20, 120, 0.17),
+            (gunslinger, 5, 170, 36, 200, 0.45),
+            (gunslinger, 10, 260, 56, 300, 0.8),
+            (netrunner, 1, 96, 21, 120, 0.15),
+            (netrunner, 5, 160, 41, 200, 0.35),
+            (netrunner, 10, 240, 66, 300, 0.6),
+            (technopriest, 1, 106, 19, 118, 0.14),
+            (technopriest, 5, 210, 31, 190, 0.3),
+            (technopriest, 10, 340, 46, 280, 0.5),
+            (clairvoyant, 1, 98, 21, 118, 0.15),
+            (clairvoyant, 5, 170, 41, 190, 0.35),
+            (clairvoyant, 10, 260, 66, 280, 0.6),
+            (nanovampire, 1, 100, 20, 126, 0.15),
+            (nanovampire, 5, 180, 36, 230, 0.35),
+            (nanovampire, 10, 280, 56, 360, 0.6),
+        ];
+        for (row, (job, level, max_health, attack, speed, crit)) in table.into_iter().enumerate() {
+            let mut world = World::new();
+            let entity = world.spawn((
+                job,
+                Level(0),
+                Xp(next_level_xp(level - 1)),
+                Stats::default(),
+                Health(0),
+            ));
+            level_up(&mut world);
+            assert_eq!(world.get::<&Level>(entity).unwrap().0, level);
+            let stats = *world.get::<&Stats>(entity).unwrap();
+            assert_eq!(
+                (stats.max_health, stats.attack, stats.speed),
+                (max_health, attack, speed),
+                "row {row}"
+            );
+            assert!((stats.crit - crit).abs() < 1e-4, "row {row}");
+            // Leveling up heals to full
+            assert_eq!(world.get::<&Health>(entity).unwrap().0, max_health);
+        }
+    }
+
+    #[test]
+    fn dead_party_members_leave_the_turn_order() {
+        let (_log, mut app) = fight(1);
+        let (fallen, name) = app
+            .world
+            .query::<With<&Name, &Party>>()
+            .iter()
+            .map(|(entity, name)| (entity, name.0))
+            .next()
+            .unwrap();
+        let upcoming = |app: &App| {
+            let next_up = app.next_up.clone().unwrap();
+            next_up
+                .take(20)
+                .map(|info| events::display_name(&app.world, info.entity))
+                .collect::<Vec<_>>()
+        };
+        assert!(upcoming(&app).iter().any(|other| other == name));
+
+        app.world.get::<&mut Health>(fallen).unwrap().0 = 0;
+        app.check_dead();
+        assert!(upcoming(&app).iter().all(|other| other != name));
+    }
+
+    #[test]
+    fn escape_chance_compares_the_fastest_on_each_side() {
+        let mut world = World::new();
+        let speed = |speed| Stats {
+            speed,
+            ..Default::default()
+        };
+        world.spawn((speed(100),));
+        world.spawn((speed(50),));
+        let enemy = world.spawn((speed(100), Hostile));
+        let chance = |world: &World, failed| escape_chance(world, true, Advantage::Neutral, failed);
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-6;
+
+        assert!(close(chance(&world, 0), BASE_FLEE_CHANCE));
+        assert!(close(
+            escape_chance(&world, true, Advantage::Enemy, 0),
+            0.25
+        ));
+        assert_eq!(escape_chance(&world, false, Advantage::Neutral, 0), 0.);
+        // Every failed attempt makes the next one likelier, up to the cap
+        assert!(close(chance(&world, 1), 0.65));
+        assert!(close(chance(&world, 2), 0.8));
+        assert!(close(chance(&world, 5), 0.95));
+
+        world.insert_one(enemy, Hasted(1)).unwrap();
+        assert!(close(chance(&world, 0), 0.25));
+        assert!(close(chance(&world, 1), 0.4));
+    }
+
+    /// Every line a fight logs with each side left to its AI, until it's over or runs long
+    fn replay(seed: u64) -> Vec<String> {
+        let mut app = start_fight(seed);
+        let mut lines = Vec::new();
+        let mut seen = LOG.lock().unwrap().written();
+        for _ in 0..100 {
+            if !matches!(app.game_state, GameState::Combat) {
+                break;
+            }
+            app.think();
+            app.end_turn();
+            let log = LOG.lock().unwrap();
+            let new = log.written() - seen;
+            seen = log.written();
+            lines.extend(log.lines.iter().skip(log.lines.len() - new).map(|line| {
+                let text = line.spans.iter().map(|span| span.content.as_ref());
+                text.collect::<String>()
+            }));
+        }
+        lines
+    }
+
+    #[test]
+    fn same_seed_same_log() {
+        let _log = LOG_ACCESS.write().unwrap_or_else(PoisonError::into_inner);
+        let first = replay(7);
+        assert!(first.len() > 20);
+        assert_eq!(first, replay(7));
+        assert_ne!(first, replay(8));
+    }
+}
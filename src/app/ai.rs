@@ -0,0 +1,313 @@
+use hecs::{Entity, World};
+use rand::prelude::*;
+
+use super::{Health, Row, Skill, Skills, Stats, Taunting, Threat, skills};
+
+/// How an enemy picks its skill and target
+#[derive(Clone, Copy, Default)]
+pub enum AiProfile {
+    /// Goes for whoever hits hardest
+    #[default]
+    Aggressive,
+    /// Picks off whoever has the least health left
+    Opportunist,
+    /// Heals its allies when they're hurt, otherwise attacks a target picked by `target_weight`
+    Support,
+    /// Avoids taunting targets and runs when badly hurt, otherwise picks like Support
+    Coward,
+}
+
+/// Health fraction an ally has to drop under before a Support heals it
+const SUPPORT_THRESHOLD: f32 = 0.5;
+/// Health fraction a Coward flees under
+const COWARD_THRESHOLD: f32 = 0.2;
+/// Taunting is hard to ignore, even for a target with no threat at full health
+const TAUNT_WEIGHT: f32 = 10.;
+
+pub enum Decision {
+    Cast {
+        skill: &'static Skill,
+        /// A single target, or everyone the skill hits if it hits many
+        targets: Vec<Entity>,
+        /// Weight of each candidate, if the target was sampled from them
+        weights: Vec<(Entity, f32)>,
+    },
+    Flee,
+}
+
+fn health_fraction(world: &World, entity: Entity) -> f32 {
+    match (world.get::<&Health>(entity), world.get::<&Stats>(entity)) {
+        (Ok(health), Ok(stats)) => health.0 as f32 / stats.max_health as f32,
+        _ => 1.,
+    }
+}
+
+/// How much the enemy wants to hit the target. Wounded targets and those who've dealt a large
+/// share of the party's recent damage weigh more, and taunting ones much more.
+pub fn target_weight(world: &World, target: Entity) -> f32 {
+    let total_threat = world
+        .query::<&Threat>()
+        .iter()
+        .map(|(_, threat)| threat.0)
+        .sum::<f32>();
+    let threat_share = match world.get::<&Threat>(target) {
+        Ok(threat) if total_threat > 0. => threat.0 / total_threat,
+        _ => 0.,
+    };
+    let mut weight = (1. + 2. * threat_share) * (1.5 - health_fraction(world, target));
+    if world.satisfies::<&Taunting>(target).unwrap_or(false) {
+        weight *= TAUNT_WEIGHT;
+    }
+    weight
+}
+
+/// Skills off cooldown the actor can pay for
+fn usable(world: &World, actor: Entity) -> Vec<&'static Skill> {
+    world
+        .get::<&Skills>(actor)
+        .map(|known| {
+            known
+                .0
+                .iter()
+                .copied()
+                .filter(|skill| {
+                    skill.cooldown_left(world, actor) == 0 && skill.can_afford(world, actor)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn decide(world: &World, actor: Entity, rng: &mut impl Rng) -> Decision {
+    let profile = world
+        .get::<&AiProfile>(actor)
+        .map_or(AiProfile::default(), |profile| *profile);
+    let usable = usable(world, actor);
+
+    if matches!(profile, AiProfile::Coward) && health_fraction(world, actor) < COWARD_THRESHOLD {
+        return Decision::Flee;
+    }
+
+    if matches!(profile, AiProfile::Support)
+        && let Some(&heal) = usable.iter().find(|skill| skill.targets_allies())
+        && let Some(hurt) = heal
+            .get_targets(world, actor)
+            .0
+            .into_iter()
+            .filter(|&ally| health_fraction(world, ally) < SUPPORT_THRESHOLD)
+            .min_by(|&a, &b| health_fraction(world, a).total_cmp(&health_fraction(world, b)))
+    {
+        return Decision::Cast {
+            skill: heal,
+            targets: vec![hurt],
+            weights: Vec::new(),
+        };
+    }
+
+    // Basic attack stays in the pool so enemies don't only ever use their skills
+    let skill = usable
+        .into_iter()
+        .filter(|skill| !skill.targets_allies())
+        .chain([&*skills::common::BASIC_ATTACK])
+        .choose(rng)
+        .unwrap_or(&skills::common::BASIC_ATTACK);
+    if skill.targets_caster() {
+        return Decision::Cast {
+            skill,
+            targets: vec![actor],
+            weights: Vec::new(),
+        };
+    }
+    let (party, many) = skill.get_targets(world, actor);
+    if many {
+        return Decision::Cast {
+            skill,
+            targets: party,
+            weights: Vec::new(),
+        };
+    }
+
+    // Confusion rerolls the target anyway once the skill is applied
+    let (taunting, not_taunting): (Vec<_>, Vec<_>) = party
+        .iter()
+        .copied()
+        .partition(|&target| world.satisfies::<&Taunting>(target).unwrap_or(false));
+    let candidates = match profile {
+        AiProfile::Coward => {
+            if not_taunting.is_empty() {
+                party
+            } else {
+                not_taunting
+            }
+        }
+        _ if !taunting.is_empty() => taunting,
+        _ => party,
+    };
+    // Melee reaches the front row more easily, so go for it while anyone's standing there
+    let front = candidates
+        .iter()
+        .copied()
+        .filter(|&target| {
+            world
+                .get::<&Row>(target)
+                .is_ok_and(|row| *row == Row::Front)
+        })
+        .collect::<Vec<_>>();
+    let candidates = if skill.melee() && !front.is_empty() {
+        front
+    } else {
+        candidates
+    };
+
+    let mut weights = Vec::new();
+    let target = match profile {
+        AiProfile::Aggressive => candidates
+            .iter()
+            .copied()
+            .max_by_key(|&target| world.get::<&Stats>(target).map_or(0, |stats| stats.attack)),
+        AiProfile::Opportunist => candidates
+            .iter()
+            .copied()
+            .min_by_key(|&target| world.get::<&Health>(target).map_or(u32::MAX, |h| h.0)),
+        AiProfile::Support | AiProfile::Coward => {
+            weights = candidates
+                .iter()
+                .map(|&target| (target, target_weight(world, target)))
+                .collect();
+            weights
+                .choose_weighted(rng, |&(_, weight)| weight)
+                .ok()
+                .map(|&(target, _)| target)
+        }
+    };
+    Decision::Cast {
+        skill,
+        targets: vec![target.unwrap_or(actor)],
+        weights,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+
+    use super::*;
+    use crate::app::Hostile;
+
+    fn combatant(world: &mut World) -> Entity {
+        world.spawn((
+            Health(100),
+            Stats {
+                max_health: 100,
+                attack: 20,
+                ..Default::default()
+            },
+        ))
+    }
+
+    fn enemy(world: &mut World, profile: AiProfile) -> Entity {
+        let enemy = combatant(world);
+        world.insert(enemy, (Hostile, profile)).unwrap();
+        enemy
+    }
+
+    fn target(decision: Decision) -> Entity {
+        match decision {
+            Decision::Cast { targets, .. } => targets[0],
+            Decision::Flee => panic!("Expected a cast"),
+        }
+    }
+
+    #[test]
+    fn taunters_split_aggro() {
+        let mut world = World::new();
+        let enemy = enemy(&mut world, AiProfile::Support);
+        let taunters = [combatant(&mut world), combatant(&mut world)];
+        for taunter in taunters {
+            world.insert_one(taunter, Taunting(2)).unwrap();
+        }
+        combatant(&mut world);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut hits = [0; 2];
+        for _ in 0..200 {
+            let target = target(decide(&world, enemy, &mut rng));
+            let taunter = taunters.iter().position(|&taunter| taunter == target);
+            hits[taunter.expect("Only taunters should be picked")] += 1;
+        }
+        assert!(hits.iter().all(|&hits| hits > 0), "{hits:?}");
+    }
+
+    #[test]
+    fn aggressive_goes_for_the_hardest_hitter() {
+        let mut world = World::new();
+        let enemy = enemy(&mut world, AiProfile::Aggressive);
+        combatant(&mut world);
+        let hitter = combatant(&mut world);
+        world.get::<&mut Stats>(hitter).unwrap().attack = 40;
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(target(decide(&world, enemy, &mut rng)), hitter);
+    }
+
+    #[test]
+    fn opportunist_picks_off_the_weakest() {
+        let mut world = World::new();
+        let enemy = enemy(&mut world, AiProfile::Opportunist);
+        combatant(&mut world);
+        let weakest = combatant(&mut world);
+        world.get::<&mut Health>(weakest).unwrap().0 = 30;
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(target(decide(&world, enemy, &mut rng)), weakest);
+    }
+
+    #[test]
+    fn support_heals_the_most_hurt_ally() {
+        let mut world = World::new();
+        let healer = enemy(&mut world, AiProfile::Support);
+        world
+            .insert_one(healer, Skills(vec![&skills::enemies::PATCH_UP]))
+            .unwrap();
+        let hurt = enemy(&mut world, AiProfile::Aggressive);
+        let worst = enemy(&mut world, AiProfile::Aggressive);
+        let party = combatant(&mut world);
+        let mut rng = StdRng::seed_from_u64(0);
+        // Nobody's under half health yet, so it attacks
+        assert_eq!(target(decide(&world, healer, &mut rng)), party);
+
+        world.get::<&mut Health>(hurt).unwrap().0 = 40;
+        world.get::<&mut Health>(worst).unwrap().0 = 30;
+        match decide(&world, healer, &mut rng) {
+            Decision::Cast { skill, targets, .. } => {
+                assert_eq!(skill.name, skills::enemies::PATCH_UP.name);
+                assert_eq!(targets, [worst]);
+            }
+            Decision::Flee => panic!("Expected a heal"),
+        }
+    }
+
+    #[test]
+    fn coward_avoids_taunters_and_flees_when_hurt() {
+        let mut world = World::new();
+        let coward = enemy(&mut world, AiProfile::Coward);
+        let taunter = combatant(&mut world);
+        world.insert_one(taunter, Taunting(2)).unwrap();
+        let other = combatant(&mut world);
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..50 {
+            assert_eq!(target(decide(&world, coward, &mut rng)), other);
+        }
+
+        world.get::<&mut Health>(coward).unwrap().0 = 10;
+        assert!(matches!(decide(&world, coward, &mut rng), Decision::Flee));
+    }
+
+    #[test]
+    fn taunting_tank_outweighs_a_wounded_healer() {
+        let mut world = World::new();
+        let tank = combatant(&mut world);
+        world.insert(tank, (Taunting(2), Threat(0.))).unwrap();
+        let healer = combatant(&mut world);
+        world.insert_one(healer, Threat(50.)).unwrap();
+        world.get::<&mut Health>(healer).unwrap().0 = 20;
+        assert!(target_weight(&world, tank) > target_weight(&world, healer));
+    }
+}
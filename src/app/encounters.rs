@@ -0,0 +1,353 @@
+use std::ops::RangeInclusive;
+
+use hecs::{Entity, With, World};
+use rand::prelude::*;
+
+use super::{
+    Advantage, AiProfile, Boss, Confused, Contagious, Health, Immunities, Level, NPCBundle, Name,
+    Party, Phase, Resistances, Row, Skills, Stats, Unscannable, XpReward,
+    skills::{self, DamageType},
+};
+
+/// An enemy in an encounter
+pub struct EnemyTemplate {
+    /// Spawns the enemy with its stats at level 1
+    pub spawn: fn(&mut World) -> Entity,
+    /// Levels relative to the party's average
+    pub levels: RangeInclusive<i8>,
+    /// XP the enemy is worth at level 1, going up with each level
+    pub xp: u32,
+}
+
+/// A group of enemies that fight together
+pub struct Encounter {
+    pub name: &'static str,
+    pub enemies: &'static [EnemyTemplate],
+    /// Who gets the jump on whom when the fight starts
+    pub advantage: Advantage,
+    /// Bosses and other set pieces can't be run from
+    pub can_flee: bool,
+    /// How common the encounter is relative to the others
+    pub weight: u32,
+    /// Reinforcements, in the order they arrive
+    pub waves: &'static [Wave],
+}
+
+/// Enemies that join a fight once it gets far enough along
+pub struct Wave {
+    pub trigger: WaveTrigger,
+    pub enemies: &'static [EnemyTemplate],
+}
+
+pub enum WaveTrigger {
+    /// Arrives at the start of the round
+    Round(u32),
+    /// Arrives once fewer enemies than this are left standing
+    FewerThan(usize),
+}
+
+impl Encounter {
+    pub fn spawn(&self, world: &mut World, rng: &mut impl Rng) {
+        spawn_templates(world, self.enemies, rng);
+    }
+}
+
+/// Spawns enemies at levels near the party's average, returning them.
+pub fn spawn_templates(
+    world: &mut World,
+    templates: &[EnemyTemplate],
+    rng: &mut impl Rng,
+) -> Vec<Entity> {
+    let party = world
+        .query::<With<&Level, &Party>>()
+        .iter()
+        .map(|(_, level)| level.0 as f32)
+        .collect::<Vec<_>>();
+    let average = (party.iter().sum::<f32>() / party.len().max(1) as f32).round() as i8;
+    templates
+        .iter()
+        .map(|template| {
+            let enemy = (template.spawn)(world);
+            let level = (average + rng.random_range(template.levels.clone())).max(1) as u8;
+            let (Level(enemy_level), stats, Health(health)) = world
+                .query_one_mut::<(&mut Level, &mut Stats, &mut Health)>(enemy)
+                .expect("Enemies must have a level, stats and health");
+            *enemy_level = level;
+            scale_stats(stats, level);
+            *health = stats.max_health;
+            world
+                .insert_one(enemy, XpReward(template.xp * level as u32))
+                .unwrap();
+            enemy
+        })
+        .collect()
+}
+
+/// Grows level 1 stats at the same rate the party's grow
+fn scale_stats(stats: &mut Stats, level: u8) {
+    let level = level as u32;
+    stats.max_health = stats.max_health * (80 + 20 * level) / 100;
+    stats.attack = stats.attack * (16 + 4 * level) / 20;
+    stats.speed = stats.speed * (100 + 20 * level) / 120;
+    stats.crit_damage += 0.05 * (level - 1) as f32;
+}
+
+pub fn random(rng: &mut impl Rng) -> &'static Encounter {
+    ENCOUNTERS
+        .choose_weighted(rng, |encounter| encounter.weight)
+        .expect("There must be an encounter to pick")
+}
+
+pub static ENCOUNTERS: [Encounter; 5] = [
+    Encounter {
+        name: "Rat Nest",
+        enemies: &[
+            EnemyTemplate {
+                spawn: cowardly_rat,
+                levels: -1..=0,
+                xp: 40,
+            },
+            EnemyTemplate {
+                spawn: sewer_rat,
+                levels: -1..=0,
+                xp: 40,
+            },
+            EnemyTemplate {
+                spawn: sewer_rat,
+                levels: -1..=1,
+                xp: 40,
+            },
+        ],
+        advantage: Advantage::Neutral,
+        can_flee: true,
+        weight: 3,
+        waves: &[Wave {
+            trigger: WaveTrigger::FewerThan(2),
+            enemies: &[
+                EnemyTemplate {
+                    spawn: sewer_rat,
+                    levels: -1..=0,
+                    xp: 40,
+                },
+                EnemyTemplate {
+                    spawn: sewer_rat,
+                    levels: -1..=0,
+                    xp: 40,
+                },
+            ],
+        }],
+    },
+    Encounter {
+        name: "Scrap Patrol",
+        enemies: &[
+            EnemyTemplate {
+                spawn: cybermutant,
+                levels: 0..=0,
+                xp: 80,
+            },
+            EnemyTemplate {
+                spawn: necro_drone,
+                levels: -1..=0,
+                xp: 60,
+            },
+        ],
+        advantage: Advantage::Neutral,
+        can_flee: true,
+        weight: 3,
+        waves: &[Wave {
+            trigger: WaveTrigger::Round(3),
+            enemies: &[EnemyTemplate {
+                spawn: cybermutant,
+                levels: -1..=0,
+                xp: 80,
+            }],
+        }],
+    },
+    Encounter {
+        name: "Mutant Ambush",
+        enemies: &[
+            EnemyTemplate {
+                spawn: cowardly_rat,
+                levels: -1..=0,
+                xp: 40,
+            },
+            EnemyTemplate {
+                spawn: cybermutant,
+                levels: 0..=1,
+                xp: 80,
+            },
+            EnemyTemplate {
+                spawn: necro_drone,
+                levels: -1..=0,
+                xp: 60,
+            },
+            EnemyTemplate {
+                spawn: sewer_rat,
+                levels: -1..=0,
+                xp: 40,
+            },
+        ],
+        advantage: Advantage::Enemy,
+        can_flee: true,
+        weight: 2,
+        waves: &[],
+    },
+    Encounter {
+        name: "Dormant Drones",
+        enemies: &[
+            EnemyTemplate {
+                spawn: necro_drone,
+                levels: 0..=1,
+                xp: 60,
+            },
+            EnemyTemplate {
+                spawn: necro_drone,
+                levels: 0..=1,
+                xp: 60,
+            },
+        ],
+        advantage: Advantage::Friendly,
+        can_flee: true,
+        weight: 2,
+        waves: &[],
+    },
+    Encounter {
+        name: "Sewer Behemoth",
+        enemies: &[EnemyTemplate {
+            spawn: sewer_behemoth,
+            levels: 1..=1,
+            xp: 400,
+        }],
+        advantage: Advantage::Neutral,
+        can_flee: false,
+        weight: 1,
+        waves: &[],
+    },
+];
+
+pub fn sewer_rat(world: &mut World) -> Entity {
+    world.spawn(NPCBundle {
+        name: Name("Sewer Rat"),
+        skills: Skills(vec![
+            &skills::enemies::BITE,
+            &skills::enemies::INFECTIOUS_BITE,
+        ]),
+        stats: Stats {
+            max_health: 60,
+            attack: 14,
+            speed: 130,
+            crit: 0.1,
+            evade: 0.1,
+            defense: 10,
+            ..Default::default()
+        },
+        resistances: Resistances::default().with(DamageType::Fire, 2.),
+        ai: AiProfile::Opportunist,
+        ..Default::default()
+    })
+}
+
+fn cowardly_rat(world: &mut World) -> Entity {
+    let rat = sewer_rat(world);
+    world.insert_one(rat, AiProfile::Coward).unwrap();
+    rat
+}
+
+fn cybermutant(world: &mut World) -> Entity {
+    world.spawn(NPCBundle {
+        name: Name("Cybermutant"),
+        skills: Skills(vec![
+            &skills::enemies::SMASH,
+            &skills::nanovampire::STATIC_DISCHARGE,
+            &skills::enemies::SCRAP_PLATING,
+        ]),
+        stats: Stats {
+            max_health: 130,
+            attack: 22,
+            speed: 90,
+            crit: 0.05,
+            defense: 30,
+            ..Default::default()
+        },
+        resistances: Resistances::default().with(DamageType::Electrical, 0.5),
+        // Machines don't catch diseases or get confused, and shake off stuns after a couple
+        immunities: Immunities::default()
+            .with::<Contagious>()
+            .with::<Confused>()
+            .diminishing(2),
+        ..Default::default()
+    })
+}
+
+fn necro_drone(world: &mut World) -> Entity {
+    world.spawn(NPCBundle {
+        name: Name("Necro-drone"),
+        skills: Skills(vec![
+            &skills::enemies::CALL_REINFORCEMENTS,
+            &skills::enemies::PATCH_UP,
+        ]),
+        stats: Stats {
+            max_health: 80,
+            attack: 12,
+            speed: 110,
+            crit: 0.05,
+            evade: 0.05,
+            defense: 20,
+            ..Default::default()
+        },
+        ai: AiProfile::Support,
+        row: Row::Back,
+        resistances: Resistances::default().with(DamageType::Electrical, 0.5),
+        ..Default::default()
+    })
+}
+
+fn sewer_behemoth(world: &mut World) -> Entity {
+    let phases = vec![
+        Phase {
+            threshold: 1.,
+            announcement: " rises from the muck!",
+            ai: AiProfile::Aggressive,
+            skills: vec![&skills::enemies::BITE, &skills::enemies::SMASH],
+            transition: None,
+        },
+        Phase {
+            threshold: 0.6,
+            announcement: " thrashes and tears off its rotting hide!",
+            ai: AiProfile::Opportunist,
+            skills: vec![
+                &skills::enemies::SMASH,
+                &skills::enemies::INFECTIOUS_BITE,
+                &skills::enemies::TAIL_SWEEP,
+            ],
+            transition: Some(&skills::enemies::SHED_SKIN),
+        },
+        Phase {
+            threshold: 0.3,
+            announcement: " shrieks, and the sewers answer!",
+            ai: AiProfile::Aggressive,
+            skills: vec![&skills::enemies::SMASH, &skills::enemies::TAIL_SWEEP],
+            transition: Some(&skills::enemies::CALL_THE_SWARM),
+        },
+    ];
+    let behemoth = world.spawn(NPCBundle {
+        name: Name("Sewer Behemoth"),
+        skills: Skills(phases[0].skills.clone()),
+        stats: Stats {
+            max_health: 450,
+            attack: 26,
+            speed: 100,
+            crit: 0.1,
+            defense: 35,
+            ..Default::default()
+        },
+        ai: phases[0].ai,
+        resistances: Resistances::default().with(DamageType::Fire, 1.5),
+        immunities: Immunities::default().with::<Confused>().diminishing(1),
+        ..Default::default()
+    });
+    world
+        .insert(behemoth, (Boss { phases, phase: 0 }, Unscannable))
+        .unwrap();
+    behemoth
+}
@@ -0,0 +1,441 @@
+use hecs::{Entity, World};
+use ratatui::{
+    style::Stylize,
+    text::{Line, Span},
+};
+
+use super::{
+    Hostile, Name, Resistances,
+    skills::{Buff, DamageType, Debuff, Response},
+};
+
+/// Something a skill or status did, for the log and anything else that needs to observe combat.
+pub enum CombatEvent {
+    SkillUsed {
+        caster: Entity,
+        skill: &'static str,
+    },
+    DamageDealt {
+        target: Entity,
+        amount: u32,
+        crit: bool,
+        damage_type: DamageType,
+        resistance: f32,
+        /// Part of the amount soaked up by a barrier
+        absorbed: u32,
+        /// Hit number and total hits for multi-hit skills
+        hit: Option<(u8, u8)>,
+    },
+    DamageTotal {
+        amount: u32,
+        hits: u8,
+    },
+    Healed {
+        target: Entity,
+        amount: u32,
+    },
+    Missed {
+        target: Entity,
+    },
+    /// A hit meant for someone already down went to the next target instead
+    Redirected {
+        from: Entity,
+        to: Entity,
+    },
+    Summoned {
+        target: Entity,
+    },
+    Scanned {
+        target: Entity,
+        /// A deep scan, reading resistances as well
+        resistances: bool,
+        /// The target kept its resistances hidden
+        hidden: bool,
+    },
+    ChargeStarted {
+        caster: Entity,
+        skill: &'static str,
+    },
+    ChargeInterrupted {
+        caster: Entity,
+        skill: &'static str,
+    },
+    /// A charged skill had nobody left to hit
+    ChargeFizzled {
+        caster: Entity,
+        skill: &'static str,
+    },
+    /// A readied follow-up went off
+    FollowUp {
+        caster: Entity,
+        target: Entity,
+    },
+    /// A reaction went off before the skill could resolve
+    Reacted {
+        reactor: Entity,
+        caster: Entity,
+        reaction: &'static str,
+        skill: &'static str,
+        response: Response,
+    },
+    /// Outcome of a random effect
+    Rolled {
+        skill: &'static str,
+        outcome: String,
+    },
+    DelayedApplied {
+        target: Entity,
+        skill: &'static str,
+        turns: u8,
+    },
+    DelayedTriggered {
+        target: Entity,
+        skill: &'static str,
+    },
+    /// A debuff bounced off the target's immunities
+    Immune {
+        target: Entity,
+        status: &'static str,
+    },
+    Killed {
+        #[allow(dead_code)] // Not logged, since the damage line already says who it was
+        target: Entity,
+    },
+    BuffApplied {
+        target: Entity,
+        buff: Buff,
+        /// The target already had it, so only its duration changed
+        refreshed: bool,
+    },
+    DebuffApplied {
+        target: Entity,
+        debuff: Debuff,
+        refreshed: bool,
+    },
+    /// A status ran out of turns
+    WoreOff {
+        target: Entity,
+        status: &'static str,
+        harmful: bool,
+    },
+    Cleansed {
+        target: Entity,
+        removed: Vec<&'static str>,
+    },
+}
+
+impl CombatEvent {
+    /// The log line for this event, if it has one. Must be called before dead entities are
+    /// despawned.
+    pub fn line(&self, world: &World) -> Option<Line<'static>> {
+        let line = match self {
+            CombatEvent::SkillUsed { caster, skill } => {
+                return Some(Line::from(vec![
+                    name_span(world, *caster),
+                    " uses ".into(),
+                    skill.blue(),
+                ]));
+            }
+            CombatEvent::DamageDealt {
+                target,
+                amount,
+                crit,
+                damage_type,
+                resistance,
+                absorbed,
+                hit,
+            } => {
+                let mut line = Line::from(vec![
+                    name_span(world, *target),
+                    " takes ".into(),
+                    format!("{amount}").bold(),
+                    if *crit {
+                        " critical ".bold()
+                    } else {
+                        " ".into()
+                    },
+                    (*damage_type).into(),
+                    " damage".into(),
+                    resistance_span(*resistance),
+                ]);
+                if *absorbed > 0 {
+                    line.push_span(format!(" ({absorbed} absorbed)").light_blue());
+                }
+                if let Some((hit, hits)) = hit {
+                    line.spans.insert(0, "  ↳ ".dark_gray());
+                    line.push_span(format!(" (hit {hit}/{hits})").dark_gray());
+                }
+                line
+            }
+            CombatEvent::DamageTotal { amount, hits } => Line::from(vec![
+                format!("{hits} hits for ").into(),
+                format!("{amount}").bold(),
+                " total damage".into(),
+            ]),
+            CombatEvent::Healed { target, amount } => Line::from(vec![
+                name_span(world, *target),
+                " recovers ".light_green(),
+                format!("{amount}").bold().light_green(),
+                " HP".light_green(),
+            ]),
+            CombatEvent::Missed { target } => {
+                Line::from(vec![name_span(world, *target), " dodges!".into()])
+            }
+            CombatEvent::Redirected { from, to } => Line::from(vec![
+                "  ↳ ".dark_gray(),
+                "Overkill! ".bold(),
+                name_span(world, *from),
+                " is down, the hit goes to ".dark_gray(),
+                name_span(world, *to),
+            ]),
+            CombatEvent::Rolled { skill, outcome } => Line::from(vec![
+                format!("{skill}!").blue(),
+                " → ".dark_gray(),
+                outcome.clone().bold(),
+            ]),
+            CombatEvent::ChargeStarted { caster, skill } => {
+                return Some(Line::from(vec![
+                    name_span(world, *caster),
+                    " begins charging ".into(),
+                    skill.blue(),
+                ]));
+            }
+            CombatEvent::ChargeInterrupted { caster, skill } => Line::from(vec![
+                name_span(world, *caster),
+                "'s ".into(),
+                skill.blue(),
+                " is interrupted!".bold(),
+            ]),
+            CombatEvent::ChargeFizzled { caster, skill } => {
+                return Some(Line::from(vec![
+                    name_span(world, *caster),
+                    "'s ".into(),
+                    skill.blue(),
+                    " fizzles with no one to hit".dark_gray(),
+                ]));
+            }
+            CombatEvent::FollowUp { caster, target } => {
+                return Some(Line::from(vec![
+                    "Combo! ".bold().light_yellow(),
+                    name_span(world, *caster),
+                    " follows up on ".into(),
+                    name_span(world, *target),
+                ]));
+            }
+            CombatEvent::Reacted {
+                reactor,
+                caster,
+                reaction,
+                skill,
+                response,
+            } => {
+                let mut line = Line::from(vec![
+                    "Reaction! ".bold().light_cyan(),
+                    name_span(world, *reactor),
+                    "'s ".into(),
+                    reaction.blue(),
+                ]);
+                match response {
+                    Response::Negate => line.extend([
+                        " negates ".into(),
+                        name_span(world, *caster),
+                        "'s ".into(),
+                        skill.blue(),
+                    ]),
+                    Response::Dodge => line.extend([
+                        " sees ".into(),
+                        skill.blue(),
+                        " coming and dodges it".into(),
+                    ]),
+                }
+                return Some(line);
+            }
+            CombatEvent::Summoned { target } => {
+                Line::from(vec![name_span(world, *target), " joins the fight!".into()])
+            }
+            CombatEvent::Scanned {
+                target,
+                resistances,
+                hidden,
+            } => {
+                let mut spans = vec![name_span(world, *target)];
+                if !*resistances {
+                    spans.push(" is scanned".into());
+                } else if *hidden {
+                    spans.push(" is scanned, but its resistances can't be read".into());
+                } else {
+                    let (weak, resists) = world
+                        .get::<&Resistances>(*target)
+                        .map(|resistances| resistances.affinities())
+                        .unwrap_or_default();
+                    if weak.is_empty() && resists.is_empty() {
+                        spans.push(" has no affinities".into());
+                    }
+                    for (label, damage_types) in [(" is weak to ", weak), (" resists ", resists)] {
+                        if damage_types.is_empty() {
+                            continue;
+                        }
+                        if spans.len() > 1 {
+                            spans.push(" and".into());
+                        }
+                        spans.push(label.into());
+                        for (i, damage_type) in damage_types.into_iter().enumerate() {
+                            if i > 0 {
+                                spans.push(", ".into());
+                            }
+                            spans.push(damage_type.into());
+                        }
+                    }
+                }
+                Line::from(spans)
+            }
+            CombatEvent::DelayedApplied {
+                target,
+                skill,
+                turns,
+            } => Line::from(vec![
+                name_span(world, *target),
+                " is marked by ".into(),
+                skill.blue(),
+                format!(" ({turns} turns)").dark_gray(),
+            ]),
+            CombatEvent::DelayedTriggered { target, skill } => Line::from(vec![
+                skill.blue(),
+                " goes off on ".into(),
+                name_span(world, *target),
+            ]),
+            CombatEvent::Immune { target, status } => Line::from(vec![
+                name_span(world, *target),
+                status_span(world, *target, format!(" is immune to {status}!"), true),
+            ]),
+            CombatEvent::Killed { .. } => return None,
+            CombatEvent::BuffApplied {
+                target,
+                buff,
+                refreshed,
+            } => {
+                let text = match buff {
+                    Buff::Haste { .. } => " is hasted",
+                    Buff::Shell { .. } => " is shelled",
+                    Buff::Taunt { .. } => " draws the enemy's attention",
+                    Buff::Barrier { .. } => " is shielded by a barrier",
+                    Buff::Ready { .. } => " readies a follow-up",
+                    Buff::React { .. } => " is ready to react",
+                    Buff::Revived => " is revived",
+                    Buff::Cleansed => " is cleansed",
+                };
+                let text = match buff.turns() {
+                    Some(turns) if *refreshed => format!("{text} again ({turns})"),
+                    Some(turns) => format!("{text} ({turns})"),
+                    None => text.to_string(),
+                };
+                Line::from(vec![
+                    name_span(world, *target),
+                    status_span(world, *target, text, true),
+                ])
+            }
+            CombatEvent::DebuffApplied {
+                target,
+                debuff,
+                refreshed,
+            } => {
+                let turns = debuff.turns();
+                let text = if *refreshed {
+                    format!("'s {} is refreshed ({turns})", debuff.status_name())
+                } else {
+                    let text = match debuff {
+                        Debuff::Burning { .. } => " is set ablaze",
+                        Debuff::Frozen { .. } => " is frozen",
+                        Debuff::Contagious { .. } => " is contagious",
+                        Debuff::Zapped { .. } => " is zapped",
+                        Debuff::Regen { .. } => " is regenerating",
+                        Debuff::Stunned { .. } => " is stunned",
+                        Debuff::Slow { .. } => " is slowed",
+                        Debuff::Confused { .. } => " is confused",
+                    };
+                    format!("{text} ({turns})")
+                };
+                Line::from(vec![
+                    name_span(world, *target),
+                    status_span(world, *target, text, debuff.beneficial()),
+                ])
+            }
+            CombatEvent::WoreOff {
+                target,
+                status,
+                harmful,
+            } => Line::from(vec![
+                name_span(world, *target),
+                status_span(world, *target, format!("'s {status} wore off"), *harmful),
+            ]),
+            CombatEvent::Cleansed { target, removed } => Line::from(vec![
+                name_span(world, *target),
+                if removed.is_empty() {
+                    " has nothing to cleanse".into()
+                } else {
+                    format!(" is cleansed of {}", removed.join(", ")).into()
+                },
+            ]),
+        };
+        Some(line.right_aligned())
+    }
+}
+
+/// The entity's name, numbered if others share it so they can be told apart.
+pub fn display_name(world: &World, entity: Entity) -> String {
+    let name = world
+        .get::<&Name>(entity)
+        .expect("Entity must have a name")
+        .0;
+    let mut namesakes = world
+        .query::<&Name>()
+        .iter()
+        .filter(|(_, other)| other.0 == name)
+        .map(|(e, _)| e)
+        .collect::<Vec<_>>();
+    if namesakes.len() < 2 {
+        return name.to_string();
+    }
+    namesakes.sort();
+    let n = namesakes.iter().position(|&e| e == entity).unwrap_or(0) + 1;
+    format!("{name} ({n})")
+}
+
+pub fn name_span(world: &World, entity: Entity) -> Span<'static> {
+    let name = display_name(world, entity);
+    if world
+        .satisfies::<&Hostile>(entity)
+        .expect("Entity not found")
+    {
+        name.red()
+    } else {
+        name.green()
+    }
+}
+
+/// Green if what happened to the target helps the party, red if it hurts it
+fn status_span(
+    world: &World,
+    target: Entity,
+    text: String,
+    good_for_target: bool,
+) -> Span<'static> {
+    let hostile = world.satisfies::<&Hostile>(target).unwrap_or(false);
+    if good_for_target != hostile {
+        text.light_green()
+    } else {
+        text.light_red()
+    }
+}
+
+fn resistance_span(resistance: f32) -> Span<'static> {
+    if resistance == 0. {
+        " (immune)".dark_gray()
+    } else if resistance < 1. {
+        " (resisted)".dark_gray()
+    } else if resistance > 1. {
+        " weak!".bold()
+    } else {
+        "".into()
+    }
+}
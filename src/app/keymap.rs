@@ -0,0 +1,326 @@
+use std::{fs, path::Path};
+
+use color_eyre::eyre::{Result, bail, eyre};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::Message;
+
+/// Messages a config file can bind keys to, by the name used there
+const ACTIONS: [(&str, Message); 11] = [
+    ("up", Message::Up),
+    ("down", Message::Down),
+    ("left", Message::Left),
+    ("right", Message::Right),
+    ("select", Message::Select),
+    ("cancel", Message::Cancel),
+    ("quit", Message::Quit),
+    ("log_up", Message::LogUp),
+    ("log_down", Message::LogDown),
+    ("log_end", Message::LogEnd),
+    ("help", Message::Help),
+];
+
+type Key = (KeyCode, KeyModifiers);
+
+/// Which message each key sends. The first key bound to a message is the one the hints show.
+pub struct KeyMap {
+    bindings: Vec<(Key, Message)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::arrows()
+    }
+}
+
+impl KeyMap {
+    fn new(bindings: &[(KeyCode, Message)]) -> Self {
+        let mut bindings = bindings
+            .iter()
+            .chain(&[(KeyCode::Char('?'), Message::Help)])
+            .map(|&(code, message)| ((code, KeyModifiers::NONE), message))
+            .collect::<Vec<_>>();
+        if cfg!(debug_assertions) {
+            bindings.push((
+                (KeyCode::Char('n'), KeyModifiers::NONE),
+                Message::NextEncounter,
+            ));
+        }
+        Self { bindings }
+    }
+
+    pub fn arrows() -> Self {
+        Self::new(&[
+            (KeyCode::Char('q'), Message::Quit),
+            (KeyCode::Esc, Message::Cancel),
+            (KeyCode::Up, Message::Up),
+            (KeyCode::Down, Message::Down),
+            (KeyCode::Left, Message::Left),
+            (KeyCode::Right, Message::Right),
+            (KeyCode::Enter, Message::Select),
+            (KeyCode::PageUp, Message::LogUp),
+            (KeyCode::PageDown, Message::LogDown),
+            (KeyCode::End, Message::LogEnd),
+        ])
+    }
+
+    /// hjkl to move, with q quitting outright rather than backing out of a menu
+    pub fn vim() -> Self {
+        let mut keys = Self::new(&[
+            (KeyCode::Char('q'), Message::Quit),
+            (KeyCode::Esc, Message::Cancel),
+            (KeyCode::Char('k'), Message::Up),
+            (KeyCode::Char('j'), Message::Down),
+            (KeyCode::Char('h'), Message::Left),
+            (KeyCode::Char('l'), Message::Right),
+            (KeyCode::Enter, Message::Select),
+            (KeyCode::Char('G'), Message::LogEnd),
+        ]);
+        keys.bindings.extend([
+            ((KeyCode::Char('u'), KeyModifiers::CONTROL), Message::LogUp),
+            (
+                (KeyCode::Char('d'), KeyModifiers::CONTROL),
+                Message::LogDown,
+            ),
+        ]);
+        keys
+    }
+
+    pub fn wasd() -> Self {
+        Self::new(&[
+            (KeyCode::Char('q'), Message::Quit),
+            (KeyCode::Esc, Message::Cancel),
+            (KeyCode::Char('w'), Message::Up),
+            (KeyCode::Char('s'), Message::Down),
+            (KeyCode::Char('a'), Message::Left),
+            (KeyCode::Char('d'), Message::Right),
+            (KeyCode::Char(' '), Message::Select),
+            (KeyCode::Enter, Message::Select),
+            (KeyCode::PageUp, Message::LogUp),
+            (KeyCode::PageDown, Message::LogDown),
+            (KeyCode::End, Message::LogEnd),
+        ])
+    }
+
+    fn preset(name: &str) -> Option<Self> {
+        match name {
+            "arrows" | "default" => Some(Self::arrows()),
+            "vim" => Some(Self::vim()),
+            "wasd" => Some(Self::wasd()),
+            _ => None,
+        }
+    }
+
+    /// Reads `action = key key ...` lines, each replacing every key bound to that action. Keys
+    /// move over from whatever they were bound to before. A `preset = vim` line swaps in a preset,
+    /// so it belongs before any overrides.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut keys = Self::default();
+        for (i, line) in fs::read_to_string(path)?.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (action, value) = line
+                .split_once('=')
+                .ok_or_else(|| eyre!("line {}: expected `action = keys`", i + 1))?;
+            let (action, value) = (action.trim(), value.trim());
+            if action == "preset" {
+                keys = Self::preset(value)
+                    .ok_or_else(|| eyre!("line {}: no preset called {value}", i + 1))?;
+                continue;
+            }
+            let &(_, message) = ACTIONS
+                .iter()
+                .find(|(name, _)| *name == action)
+                .ok_or_else(|| eyre!("line {}: no action called {action}", i + 1))?;
+            let rebound = value
+                .split_whitespace()
+                .map(|key| parse_key(key).ok_or_else(|| eyre!("line {}: no key {key}", i + 1)))
+                .collect::<Result<Vec<_>>>()?;
+            keys.bindings
+                .retain(|&(key, bound)| bound != message && !rebound.contains(&key));
+            keys.bindings
+                .extend(rebound.into_iter().map(|key| (key, message)));
+        }
+        for required in [Message::Quit, Message::Cancel] {
+            let reachable = keys.bindings.iter().any(|&((code, modifiers), bound)| {
+                bound == required && keys.get(KeyEvent::new(code, modifiers)) == Some(required)
+            });
+            if !reachable {
+                let (name, _) = ACTIONS.iter().find(|(_, m)| *m == required).unwrap();
+                bail!("{} leaves {name} unbound", path.display());
+            }
+        }
+        Ok(keys)
+    }
+
+    pub fn get(&self, key: KeyEvent) -> Option<Message> {
+        // Shift is already in the character itself
+        let modifiers = match key.code {
+            KeyCode::Char(_) => key.modifiers - KeyModifiers::SHIFT,
+            _ => key.modifiers,
+        };
+        self.bindings
+            .iter()
+            .find(|&&(bound, _)| bound == (key.code, modifiers))
+            .map(|&(_, message)| message)
+    }
+
+    fn first(&self, message: Message) -> Option<Key> {
+        self.bindings
+            .iter()
+            .find(|&&(_, bound)| bound == message)
+            .map(|&(key, _)| key)
+    }
+
+    /// Every action a config file can bind, with all of the keys bound to it
+    pub fn actions(&self) -> Vec<(&'static str, String)> {
+        ACTIONS
+            .iter()
+            .map(|&(name, message)| {
+                let keys = self
+                    .bindings
+                    .iter()
+                    .filter(|&&(_, bound)| bound == message)
+                    .map(|&(key, _)| key_label(key))
+                    .collect::<Vec<_>>();
+                (name, keys.join(" "))
+            })
+            .collect()
+    }
+
+    /// The keys for the messages in brackets, like "(↓↑)" or "(pgup/pgdn/end)"
+    pub fn hint(&self, messages: &[Message]) -> String {
+        let labels = messages
+            .iter()
+            .filter_map(|&message| self.first(message))
+            .map(key_label)
+            .collect::<Vec<_>>();
+        let separator = if labels.iter().all(|label| label.chars().count() == 1) {
+            ""
+        } else {
+            "/"
+        };
+        format!("({})", labels.join(separator))
+    }
+}
+
+fn parse_key(key: &str) -> Option<Key> {
+    let (modifiers, key) = match key.split_once('+') {
+        Some(("ctrl", key)) => (KeyModifiers::CONTROL, key),
+        Some(("alt", key)) => (KeyModifiers::ALT, key),
+        Some(_) => return None,
+        None => (KeyModifiers::NONE, key),
+    };
+    let code = match key {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "space" => KeyCode::Char(' '),
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pgup" => KeyCode::PageUp,
+        "pgdn" => KeyCode::PageDown,
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return None,
+            }
+        }
+    };
+    Some((code, modifiers))
+}
+
+fn key_label((code, modifiers): Key) -> String {
+    let key = match code {
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::PageUp => "pgup".to_string(),
+        KeyCode::PageDown => "pgdn".to_string(),
+        code => code.to_string().to_lowercase(),
+    };
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        format!("ctrl+{key}")
+    } else if modifiers.contains(KeyModifiers::ALT) {
+        format!("alt+{key}")
+    } else {
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loads the config from a file of its own, so tests can run side by side
+    fn load(config: &str) -> Result<KeyMap> {
+        let path = std::env::temp_dir().join(format!(
+            "term-jrpg-keys-{}-{:?}.conf",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        fs::write(&path, config)?;
+        let keys = KeyMap::load(&path);
+        fs::remove_file(&path)?;
+        keys
+    }
+
+    fn sends(keys: &KeyMap, code: KeyCode, modifiers: KeyModifiers) -> Option<Message> {
+        keys.get(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn override_moves_the_key_off_its_old_action() {
+        let keys = load("quit = ctrl+c\nhelp = q h\nleft = left up\n").unwrap();
+        assert!(sends(&keys, KeyCode::Char('q'), KeyModifiers::NONE) == Some(Message::Help));
+        assert!(sends(&keys, KeyCode::Char('c'), KeyModifiers::CONTROL) == Some(Message::Quit));
+        assert!(sends(&keys, KeyCode::Char('?'), KeyModifiers::NONE).is_none());
+        assert!(sends(&keys, KeyCode::Up, KeyModifiers::NONE) == Some(Message::Left));
+        assert!(sends(&keys, KeyCode::Enter, KeyModifiers::NONE) == Some(Message::Select));
+        assert_eq!(keys.hint(&[Message::Quit]), "(ctrl+c)");
+    }
+
+    #[test]
+    fn preset_comes_before_overrides() {
+        let keys = load("# hjkl, but w for up\npreset = vim\n\nup = w\n").unwrap();
+        assert!(sends(&keys, KeyCode::Char('w'), KeyModifiers::NONE) == Some(Message::Up));
+        assert!(sends(&keys, KeyCode::Char('k'), KeyModifiers::NONE).is_none());
+        assert!(sends(&keys, KeyCode::Char('j'), KeyModifiers::NONE) == Some(Message::Down));
+        assert!(sends(&keys, KeyCode::Up, KeyModifiers::NONE).is_none());
+    }
+
+    #[test]
+    fn bad_lines_are_errors() {
+        let error = |config: &str| load(config).err().unwrap().to_string();
+        assert_eq!(error("up\n"), "line 1: expected `action = keys`");
+        assert_eq!(error("up = w\njump = j\n"), "line 2: no action called jump");
+        assert_eq!(error("up = ctrl+shift+k\n"), "line 1: no key ctrl+shift+k");
+        assert_eq!(error("preset = emacs\n"), "line 1: no preset called emacs");
+    }
+
+    #[test]
+    fn quit_and_cancel_must_stay_bound() {
+        let error = |config: &str| load(config).err().unwrap().to_string();
+        assert!(error("quit =\n").ends_with("leaves quit unbound"));
+        // Taking the only key cancel had leaves it with none
+        assert!(error("select = esc\n").ends_with("leaves cancel unbound"));
+        assert!(load("select = esc\ncancel = backspace\n").is_ok());
+    }
+}
@@ -1,17 +1,116 @@
-use std::fmt::Display;
+use std::{fmt::Display, sync::LazyLock};
 
-use hecs::{Entity, EntityRef, Satisfies, World};
+use hecs::{Entity, EntityRef, Satisfies, Without, World};
 use rand::prelude::*;
-use ratatui::{
-    style::Stylize,
-    text::{Line, Span},
-};
+use ratatui::{style::Stylize, text::Span};
 
-use super::{Burning, Health, Hostile, Job, LOG, Name, Party, Stats};
+use super::{
+    BASE_CRIT_DAMAGE, Barrier, Blind, Burning, Confused, Contagious, Cooldowns, DEBUFFS, Dead,
+    Defending, Frozen, Ghost, Hasted, Health, Hostile, Immunities, Initiative, Job, Level,
+    MAX_BURNING_STACKS, NPCBundle, Name, OVERHEAT, Party, Reaction, Readied, Regen, Resistances,
+    Row, Scanned, Shelled, Skills, Slowed, Stats, Status, Stunned, Summoned, Taunting, Threat,
+    Unscannable, XpReward, Zapped,
+    encounters::{self, EnemyTemplate},
+    events::CombatEvent,
+};
 
+pub mod clairvoyant;
 pub mod common;
+pub mod enemies;
 pub mod gunslinger;
 pub mod nanovampire;
+pub mod netrunner;
+mod predicates;
+pub mod technopriest;
+
+use predicates::*;
+
+const MAX_AMMO: u8 = 6;
+const MAX_RAM: u8 = 16;
+const MAX_BATTERY: u8 = 100;
+const BASE_MAX_PRAYERS: u8 = 4;
+
+/// Suns, and separately moons, a Clairvoyant can hold
+const MAX_CELESTIAL: u8 = 5;
+
+/// Technopriests gain another prayer slot every other level
+fn max_prayers(level: u8) -> u8 {
+    BASE_MAX_PRAYERS + level / 2
+}
+
+/// The most of each resource the job can hold at the given level. Heat has no cap, so its
+/// maximum is where it overheats.
+pub fn max_resources(job: &Job, level: u8) -> Job {
+    match job {
+        Job::Gunslinger { .. } => Job::Gunslinger { ammo: MAX_AMMO },
+        Job::Netrunner { .. } => Job::Netrunner {
+            ram: MAX_RAM,
+            heat: OVERHEAT,
+        },
+        Job::Technopriest { .. } => Job::Technopriest {
+            prayers: max_prayers(level),
+        },
+        Job::Clairvoyant { .. } => Job::Clairvoyant {
+            sun: MAX_CELESTIAL,
+            moon: MAX_CELESTIAL,
+        },
+        Job::Nanovampire { .. } => Job::Nanovampire {
+            battery: MAX_BATTERY,
+        },
+        Job::None => Job::None,
+    }
+}
+
+/// A skill worth steering the caster towards when they open the skill menu, like reloading an
+/// empty gun.
+pub fn suggested_skill(world: &World, caster: Entity) -> Option<&'static Skill> {
+    match *world.get::<&Job>(caster).ok()? {
+        Job::Gunslinger { ammo: 0 } => Some(&gunslinger::RELOAD),
+        _ => None,
+    }
+}
+
+/// Battery a Nanovampire loses at the end of each of their turns
+const BATTERY_DRAIN: u8 = 2;
+
+/// Physical damage multiplier for each of the caster and target standing in the back row
+const BACK_ROW_DAMAGE: f32 = 0.75;
+
+/// Resources that change on their own at the end of each turn. Technopriests regain a prayer and
+/// Nanovampires' batteries run down.
+pub fn tick_resources(world: &mut World, entity: Entity) {
+    let Ok(job) = world.get::<&Job>(entity).as_deref().copied() else {
+        return;
+    };
+    match job {
+        Job::Technopriest { .. } => gain_resource(world, entity, Job::Technopriest { prayers: 1 }),
+        Job::Nanovampire { .. } => drain_resource(
+            world,
+            entity,
+            Job::Nanovampire {
+                battery: BATTERY_DRAIN,
+            },
+        ),
+        _ => (),
+    }
+}
+
+/// Every skill the job has learned by the given level, in unlock order.
+pub fn skills_for(job: &Job, level: u8) -> Vec<&'static Skill> {
+    let unlocks: &[(u8, &'static LazyLock<Skill>)] = match job {
+        Job::Gunslinger { .. } => &gunslinger::UNLOCKS,
+        Job::Clairvoyant { .. } => &clairvoyant::UNLOCKS,
+        Job::Nanovampire { .. } => &nanovampire::UNLOCKS,
+        Job::Netrunner { .. } => &netrunner::UNLOCKS,
+        Job::Technopriest { .. } => &technopriest::UNLOCKS,
+        _ => &[],
+    };
+    unlocks
+        .iter()
+        .filter(|&&(unlock, _)| unlock <= level)
+        .map(|&(_, skill)| &**skill)
+        .collect()
+}
 
 #[derive(Clone, Copy, Debug)]
 pub enum DamageType {
@@ -25,6 +124,19 @@ pub enum DamageType {
     Light,
 }
 
+impl DamageType {
+    pub const ALL: [DamageType; 8] = [
+        DamageType::Physical,
+        DamageType::Healing,
+        DamageType::Fire,
+        DamageType::Ice,
+        DamageType::Toxic,
+        DamageType::Electrical,
+        DamageType::Dark,
+        DamageType::Light,
+    ];
+}
+
 impl Display for DamageType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -46,6 +158,8 @@ impl<'a> From<DamageType> for Span<'a> {
     }
 }
 
+// Skills don't use every debuff yet
+#[allow(dead_code)]
 #[derive(Clone, Copy)]
 pub enum Debuff {
     Burning { stacks: u8, duration: u8 },
@@ -58,14 +172,110 @@ pub enum Debuff {
     Confused { duration: u8 },
 }
 
+impl Debuff {
+    /// Name of the status component the debuff applies
+    pub fn status_name(&self) -> &'static str {
+        match self {
+            Debuff::Burning { .. } => Burning::NAME,
+            Debuff::Frozen { .. } => Frozen::NAME,
+            Debuff::Contagious { .. } => Contagious::NAME,
+            Debuff::Zapped { .. } => Zapped::NAME,
+            Debuff::Regen { .. } => Regen::NAME,
+            Debuff::Stunned { .. } => Stunned::NAME,
+            Debuff::Slow { .. } => Slowed::NAME,
+            Debuff::Confused { .. } => Confused::NAME,
+        }
+    }
+
+    pub fn turns(&self) -> u8 {
+        match *self {
+            Debuff::Burning { duration, .. }
+            | Debuff::Contagious { duration }
+            | Debuff::Zapped { duration }
+            | Debuff::Regen { duration, .. }
+            | Debuff::Stunned { duration }
+            | Debuff::Slow { duration }
+            | Debuff::Confused { duration } => duration,
+            Debuff::Frozen { amount } => amount,
+        }
+    }
+
+    /// Regen goes through the debuff machinery but helps whoever has it
+    pub fn beneficial(&self) -> bool {
+        matches!(self, Debuff::Regen { .. })
+    }
+}
+
+/// Picks the target for a readied follow-up out of an ally's combat event, if the event sets it
+/// off.
+pub type Trigger = fn(&CombatEvent) -> Option<Entity>;
+
+/// Whether an opposing skill about to go off sets off the reactor's reaction
+pub type Interrupt = fn(skill: &Skill, targets: &[Entity], reactor: Entity) -> bool;
+
+/// What a reaction does to the skill that set it off
+#[derive(Clone, Copy)]
+pub enum Response {
+    /// The skill goes off without doing anything
+    Negate,
+    /// The reactor gets out of the way, which leaves a single-target skill nothing to hit
+    Dodge,
+}
+
 #[derive(Clone, Copy)]
 pub enum Buff {
-    Haste { duration: u8 },
-    Shell { duration: u8 },
+    Haste {
+        duration: u8,
+    },
+    Shell {
+        duration: u8,
+    },
+    Taunt {
+        duration: u8,
+    },
+    Barrier {
+        amount: u32,
+    },
+    /// Readies a free follow-up skill for when an ally's action sets it off
+    Ready {
+        skill: &'static Skill,
+        trigger: Trigger,
+        duration: u8,
+    },
+    /// Answers the next opposing skill that sets off the trigger, until the round ends
+    React {
+        name: &'static str,
+        trigger: Interrupt,
+        response: Response,
+    },
     Revived,
     Cleansed,
 }
 
+impl Buff {
+    /// How long the buff lasts, for those that wear off
+    pub fn turns(&self) -> Option<u8> {
+        match *self {
+            Buff::Haste { duration }
+            | Buff::Shell { duration }
+            | Buff::Taunt { duration }
+            | Buff::Ready { duration, .. } => Some(duration),
+            _ => None,
+        }
+    }
+
+    /// Name of the status component the buff applies, for those that wear off
+    pub fn status_name(&self) -> Option<&'static str> {
+        match self {
+            Buff::Haste { .. } => Some(Hasted::NAME),
+            Buff::Shell { .. } => Some(Shelled::NAME),
+            Buff::Taunt { .. } => Some(Taunting::NAME),
+            Buff::Ready { .. } => Some(Readied::NAME),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 enum PrimaryTarget {
     Caster,
@@ -73,47 +283,93 @@ enum PrimaryTarget {
     AllHostile,
     Friendly,
     AllFriendly,
+    DeadFriendly,
     Any,
     All,
 }
 
+#[allow(dead_code)]
 #[derive(Clone, Copy)]
 enum EffectTarget {
     Target,
     Caster,
     Hostile,
     Friendly,
+    OtherFriendly,
     All,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 struct Damage {
     damage_type: DamageType,
     multiplier: f32,
     crit_multiplier: f32,
     hits: u8,
     randomized: bool,
-    modifier: Option<DamageModifier>,
+    variance: f32,
+    lifesteal: f32,
+    /// Flat damage used in place of the caster's attack, ignoring the target's defense too
+    base: Option<u32>,
+    /// Fraction of damage dealt recharged into the caster's battery
+    recharge: f32,
+    /// Extra multiplier per caster level, e.g. 0.05 is +5% per level
+    scaling: f32,
+    /// Multiplies by the resources spent on a `Cost::All`
+    per_spent: bool,
+    /// Hits once per resource spent on a `Cost::All`
+    hits_per_spent: bool,
+    /// Reaches the back row at full strength, and hits as hard from it
+    ignores_rows: bool,
+    modifiers: Vec<DamageModifier>,
 }
 
 impl Damage {
+    /// Applies every modifier whose test passes, in order. Multipliers stack multiplicatively
+    /// on top of the base multiplier, while damage type and crit multiplier are overridden by
+    /// the last passing modifier that sets them.
     fn get_modified(&self, caster: EntityRef, target: EntityRef) -> Self {
-        if let Some(modifier) = self.modifier {
-            if modifier.test.0(caster, target) {
-                return Self {
-                    damage_type: modifier.damage_type.unwrap_or(self.damage_type),
-                    multiplier: modifier.multiplier.unwrap_or(self.multiplier),
-                    crit_multiplier: modifier.crit_multiplier.unwrap_or(self.crit_multiplier),
-                    ..self.clone()
-                };
+        // Runs once per hit, so the result doesn't carry the modifiers along
+        let mut modified = Self {
+            modifiers: Vec::new(),
+            ..*self
+        };
+        for modifier in self.modifiers.iter() {
+            if modifier.test.passes(caster, target) {
+                if let Some(damage_type) = modifier.damage_type {
+                    modified.damage_type = damage_type;
+                }
+                if let Some(multiplier) = modifier.multiplier {
+                    modified.multiplier *= multiplier;
+                }
+                if let Some(crit_multiplier) = modifier.crit_multiplier {
+                    modified.crit_multiplier = crit_multiplier;
+                }
             }
         }
-        self.clone()
+        modified
     }
 }
 
+/// A check against the caster and a target. Combinators borrow `'static` slices so effects
+/// holding them stay `Copy`.
 #[derive(Clone, Copy)]
-struct TestFn(fn(caster: EntityRef, target: EntityRef) -> bool);
+enum TestFn {
+    Is(fn(caster: EntityRef, target: EntityRef) -> bool),
+    AllOf(&'static [TestFn]),
+    AnyOf(&'static [TestFn]),
+    Not(&'static TestFn),
+}
+
+impl TestFn {
+    fn passes(&self, caster: EntityRef, target: EntityRef) -> bool {
+        match self {
+            TestFn::Is(test) => test(caster, target),
+            TestFn::AllOf(tests) => tests.iter().all(|test| test.passes(caster, target)),
+            TestFn::AnyOf(tests) => tests.iter().any(|test| test.passes(caster, target)),
+            TestFn::Not(test) => !test.passes(caster, target),
+        }
+    }
+}
 
 #[derive(Clone, Copy)]
 struct DamageModifier {
@@ -126,7 +382,7 @@ struct DamageModifier {
 impl Default for DamageModifier {
     fn default() -> Self {
         Self {
-            test: TestFn(is_burning),
+            test: TestFn::Is(is_burning),
             damage_type: None,
             multiplier: None,
             crit_multiplier: None,
@@ -157,6 +413,7 @@ impl DamageBuilder {
         self
     }
 
+    #[allow(dead_code)]
     fn crit(mut self, crit: f32) -> Self {
         self.damage.crit_multiplier = crit;
         self
@@ -172,13 +429,54 @@ impl DamageBuilder {
         self
     }
 
+    #[allow(dead_code)]
+    fn variance(mut self, variance: f32) -> Self {
+        self.damage.variance = variance;
+        self
+    }
+
+    fn lifesteal(mut self, lifesteal: f32) -> Self {
+        self.damage.lifesteal = lifesteal;
+        self
+    }
+
+    fn base(mut self, base: u32) -> Self {
+        self.damage.base = Some(base);
+        self
+    }
+
+    fn recharge(mut self, recharge: f32) -> Self {
+        self.damage.recharge = recharge;
+        self
+    }
+
+    fn scaling(mut self, scaling: f32) -> Self {
+        self.damage.scaling = scaling;
+        self
+    }
+
+    fn per_spent(mut self) -> Self {
+        self.damage.per_spent = true;
+        self
+    }
+
+    fn hits_per_spent(mut self) -> Self {
+        self.damage.hits_per_spent = true;
+        self
+    }
+
+    fn ignores_rows(mut self) -> Self {
+        self.damage.ignores_rows = true;
+        self
+    }
+
     fn target(mut self, target: EffectTarget) -> Self {
         self.target = target;
         self
     }
 
     fn modifier(mut self, modifier: DamageModifier) -> Self {
-        self.damage.modifier = Some(modifier);
+        self.damage.modifiers.push(modifier);
         self
     }
 
@@ -195,7 +493,15 @@ impl Default for Damage {
             crit_multiplier: 1.5,
             hits: 1,
             randomized: false,
-            modifier: None,
+            variance: 0.1,
+            lifesteal: 0.,
+            base: None,
+            recharge: 0.,
+            scaling: 0.,
+            per_spent: false,
+            hits_per_spent: false,
+            ignores_rows: false,
+            modifiers: Vec::new(),
         }
     }
 }
@@ -208,9 +514,51 @@ enum Effect {
     Gain(Job),
     Drain(Job),
     Conditional(TestFn, Vec<Effect>),
+    /// Brings new combatants onto the caster's side, at levels near the party's average
+    Summon(&'static [EnemyTemplate]),
+    /// Reveals the targets' health, and their resistances too if asked
+    Scan {
+        resistances: bool,
+    },
+    /// Goes off on each target after that many of the target's turns
+    Delayed {
+        turns: u8,
+        effects: Vec<Effect>,
+    },
+    /// One of the effects, picked by weight on each cast
+    Random(Vec<(u8, Effect)>),
+    /// Sub-effects go off on each target with this chance
+    Chance(f64, Vec<Effect>),
 }
 
 impl Effect {
+    /// What the effect does in a couple of words, for logging random outcomes
+    fn describe(&self) -> String {
+        match self {
+            Effect::Damage(damage, _) => format!("{} damage", damage.damage_type),
+            Effect::Buff(buff, _) => match buff {
+                Buff::Haste { .. } => "Haste",
+                Buff::Shell { .. } => "Shell",
+                Buff::Taunt { .. } => "Taunt",
+                Buff::Barrier { .. } => "Barrier",
+                Buff::Ready { .. } => "Ready",
+                Buff::React { name, .. } => *name,
+                Buff::Revived => "Revive",
+                Buff::Cleansed => "Cleanse",
+            }
+            .to_string(),
+            Effect::Debuff(debuff, _) => debuff.status_name().to_string(),
+            Effect::Gain(_) => "Recharge".to_string(),
+            Effect::Drain(_) => "Drain".to_string(),
+            Effect::Summon(_) => "Summon".to_string(),
+            Effect::Scan { .. } => "Scan".to_string(),
+            Effect::Delayed { .. } => "Delayed".to_string(),
+            Effect::Conditional(..) | Effect::Random(_) | Effect::Chance(..) => {
+                "Something".to_string()
+            }
+        }
+    }
+
     fn damage() -> DamageBuilder {
         DamageBuilder::new()
     }
@@ -223,12 +571,20 @@ impl Effect {
 #[derive(Clone)]
 pub struct Skill {
     pub name: &'static str,
+    pub description: &'static str,
     target: PrimaryTarget,
     effects: Vec<Effect>,
     on_hit: Vec<Effect>,
     on_crit: Vec<Effect>,
-    cost: Job,
-    modifier: Option<SkillModifier>,
+    on_miss: Vec<Effect>,
+    on_kill: Vec<Effect>,
+    cost: Cost,
+    /// Turns the caster has to wait before using the skill again
+    cooldown: u8,
+    /// Fires at the start of the caster's next turn instead of right away
+    charged: bool,
+    /// The first modifier whose test passes for the caster replaces parts of the skill
+    modifiers: Vec<SkillModifier>,
 }
 
 #[derive(Clone)]
@@ -237,28 +593,144 @@ struct SkillModifier {
     effects: Option<Vec<Effect>>,
     on_hit: Option<Vec<Effect>>,
     on_crit: Option<Vec<Effect>>,
-    cost: Option<Job>,
+    on_miss: Option<Vec<Effect>>,
+    on_kill: Option<Vec<Effect>>,
+    cost: Option<Cost>,
 }
 
 impl Default for SkillModifier {
     fn default() -> Self {
         Self {
-            test: TestFn(is_burning),
+            test: TestFn::Is(is_burning),
             effects: None,
             on_hit: None,
             on_crit: None,
+            on_miss: None,
+            on_kill: None,
             cost: None,
         }
     }
 }
 
+/// What the caster pays to use a skill
+#[derive(Clone)]
+pub enum Cost {
+    Job(Job),
+    #[allow(dead_code)]
+    Health(u32),
+    /// Fraction of max health
+    HealthPercent(f32),
+    /// Everything the job has, as long as it has at least this much
+    All(Job),
+    Multiple(Vec<Cost>),
+}
+
+impl Default for Cost {
+    fn default() -> Self {
+        Cost::Job(Job::None)
+    }
+}
+
+impl From<Job> for Cost {
+    fn from(job: Job) -> Self {
+        Cost::Job(job)
+    }
+}
+
+impl Display for Cost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cost::Job(Job::None) => write!(f, "Free"),
+            Cost::Job(Job::Gunslinger { ammo }) => write!(f, "{ammo} ammo"),
+            Cost::Job(Job::Netrunner { ram, heat }) => write!(f, "{ram}GB RAM, {heat}ºC"),
+            Cost::Job(Job::Technopriest { prayers }) => write!(f, "{prayers} prayers"),
+            Cost::Job(Job::Clairvoyant { sun, moon }) => write!(f, "{sun} sun, {moon} moon"),
+            Cost::Job(Job::Nanovampire { battery }) => write!(f, "{battery}% battery"),
+            Cost::Health(health) => write!(f, "{health} HP"),
+            Cost::HealthPercent(fraction) => write!(f, "{}% HP", (fraction * 100.).round()),
+            Cost::All(job) => write!(f, "All ({} min)", Cost::Job(*job)),
+            Cost::Multiple(costs) => {
+                let costs = costs.iter().map(Cost::to_string).collect::<Vec<_>>();
+                write!(f, "{}", costs.join(" + "))
+            }
+        }
+    }
+}
+
+impl Cost {
+    fn health(&self, world: &World, entity: Entity) -> u32 {
+        match self {
+            Cost::Health(health) => *health,
+            Cost::HealthPercent(fraction) => world.get::<&Stats>(entity).map_or(0, |stats| {
+                (stats.max_health as f32 * fraction).round() as u32
+            }),
+            _ => 0,
+        }
+    }
+
+    fn can_afford(&self, world: &World, entity: Entity) -> bool {
+        match self {
+            Cost::Job(job) => can_afford(world, entity, *job),
+            // Health costs can't kill, so they only need something left to pay with
+            Cost::Health(_) | Cost::HealthPercent(_) => world
+                .get::<&Health>(entity)
+                .is_ok_and(|health| health.0 > 1),
+            Cost::All(job) => can_afford(world, entity, *job),
+            Cost::Multiple(costs) => costs.iter().all(|cost| cost.can_afford(world, entity)),
+        }
+    }
+
+    /// Returns how much was spent on `Cost::All`, for effects that scale with it.
+    fn pay(&self, world: &mut World, entity: Entity) -> u32 {
+        match self {
+            Cost::Job(job) => spend_resource(world, entity, *job),
+            Cost::Health(_) | Cost::HealthPercent(_) => {
+                let amount = self.health(world, entity);
+                if let Ok(mut health) = world.get::<&mut Health>(entity) {
+                    health.0 = health.0.saturating_sub(amount).max(1);
+                }
+            }
+            Cost::All(_) => {
+                let Ok(job) = world.get::<&Job>(entity).as_deref().copied() else {
+                    return 0;
+                };
+                drain_resource(world, entity, job);
+                return match job {
+                    Job::None => 0,
+                    Job::Gunslinger { ammo } => ammo as u32,
+                    Job::Netrunner { ram, .. } => ram as u32,
+                    Job::Technopriest { prayers } => prayers as u32,
+                    Job::Clairvoyant { sun, moon } => sun as u32 + moon as u32,
+                    Job::Nanovampire { battery } => battery as u32,
+                };
+            }
+            Cost::Multiple(costs) => {
+                return costs.iter().map(|cost| cost.pay(world, entity)).sum();
+            }
+        }
+        0
+    }
+}
+
+/// Pays a job cost. Heat is the one resource a cost adds to rather than takes from.
+fn spend_resource(world: &mut World, entity: Entity, cost: Job) {
+    match cost {
+        Job::Netrunner { ram, heat } => {
+            drain_resource(world, entity, Job::Netrunner { ram, heat: 0 });
+            gain_resource(world, entity, Job::Netrunner { ram: 0, heat });
+        }
+        cost => drain_resource(world, entity, cost),
+    }
+}
+
 fn drain_resource(world: &mut World, entity: Entity, amount: Job) {
     if matches!(amount, Job::None) {
         return;
     }
-    let job = world
-        .query_one_mut::<&mut Job>(entity)
-        .expect("Entity must have a job component");
+    // Enemies don't have a job, so their skills only answer to cooldowns
+    let Ok(job) = world.query_one_mut::<&mut Job>(entity) else {
+        return;
+    };
 
     match job {
         Job::Gunslinger { ammo } => {
@@ -306,90 +778,571 @@ fn drain_resource(world: &mut World, entity: Entity, amount: Job) {
     }
 }
 
+fn can_afford(world: &World, entity: Entity, cost: Job) -> bool {
+    if matches!(cost, Job::None) {
+        return true;
+    }
+    let Ok(job) = world.get::<&Job>(entity) else {
+        return true;
+    };
+
+    match (*job, cost) {
+        (Job::Gunslinger { ammo }, Job::Gunslinger { ammo: ammo_cost }) => ammo >= ammo_cost,
+        (
+            Job::Netrunner { ram, heat },
+            Job::Netrunner {
+                ram: ram_cost,
+                heat: heat_cost,
+            },
+        ) => ram >= ram_cost && heat.saturating_add(heat_cost) <= OVERHEAT,
+        (
+            Job::Technopriest { prayers },
+            Job::Technopriest {
+                prayers: prayers_cost,
+            },
+        ) => prayers >= prayers_cost,
+        (
+            Job::Clairvoyant { sun, moon },
+            Job::Clairvoyant {
+                sun: sun_cost,
+                moon: moon_cost,
+            },
+        ) => sun >= sun_cost && moon >= moon_cost,
+        (
+            Job::Nanovampire { battery },
+            Job::Nanovampire {
+                battery: battery_cost,
+            },
+        ) => battery >= battery_cost,
+        // Can't pay with another job's resources
+        _ => false,
+    }
+}
+
 fn gain_resource(world: &mut World, entity: Entity, amount: Job) {
     if matches!(amount, Job::None) {
         return;
     }
-    // TODO: Fetch max amounts from somewhere
-    let job = world
-        .query_one_mut::<&mut Job>(entity)
-        .expect("Entity must have a job component");
+    let Ok((job, level)) = world.query_one_mut::<(&mut Job, Option<&Level>)>(entity) else {
+        return;
+    };
+    let max = max_resources(job, level.map_or(1, |level| level.0));
 
-    match job {
-        Job::Gunslinger { ammo } => {
+    match (job, max) {
+        (Job::Gunslinger { ammo }, Job::Gunslinger { ammo: max_ammo }) => {
             if let Job::Gunslinger { ammo: ammo_gain } = amount {
-                *ammo = ammo.saturating_add(ammo_gain).min(6);
+                *ammo = ammo.saturating_add(ammo_gain).min(max_ammo);
             }
         }
-        Job::Netrunner { ram, heat } => {
+        (Job::Netrunner { ram, heat }, Job::Netrunner { ram: max_ram, .. }) => {
             if let Job::Netrunner {
                 ram: ram_gain,
                 heat: heat_gain,
             } = amount
             {
-                *ram = ram.saturating_add(ram_gain);
+                *ram = ram.saturating_add(ram_gain).min(max_ram);
                 *heat = heat.saturating_add(heat_gain);
             }
         }
-        Job::Technopriest { prayers } => {
+        (
+            Job::Technopriest { prayers },
+            Job::Technopriest {
+                prayers: max_prayers,
+            },
+        ) => {
             if let Job::Technopriest {
                 prayers: prayers_gain,
             } = amount
             {
-                *prayers = prayers.saturating_add(prayers_gain);
+                *prayers = prayers.saturating_add(prayers_gain).min(max_prayers);
             }
         }
-        Job::Clairvoyant { sun, moon } => {
+        (
+            Job::Clairvoyant { sun, moon },
+            Job::Clairvoyant {
+                sun: max_sun,
+                moon: max_moon,
+            },
+        ) => {
             if let Job::Clairvoyant {
                 sun: sun_gain,
                 moon: moon_gain,
             } = amount
             {
-                *sun = sun.saturating_add(sun_gain);
-                *moon = moon.saturating_add(moon_gain);
+                *sun = sun.saturating_add(sun_gain).min(max_sun);
+                *moon = moon.saturating_add(moon_gain).min(max_moon);
             }
         }
-        Job::Nanovampire { battery } => {
+        (
+            Job::Nanovampire { battery },
+            Job::Nanovampire {
+                battery: max_battery,
+            },
+        ) => {
             if let Job::Nanovampire {
                 battery: battery_gain,
             } = amount
             {
-                *battery = battery.saturating_add(battery_gain);
+                *battery = battery.saturating_add(battery_gain).min(max_battery);
             }
         }
-        Job::None => (),
+        _ => (),
     }
 }
 
-impl Skill {
-    fn get_modified(&self, caster: EntityRef) -> Skill {
-        if let Some(modifier) = &self.modifier {
-            // Test functions take both caster and target for reusability.
-            if modifier.test.0(caster, caster) {
-                return Skill {
-                    modifier: None,
-                    effects: modifier.effects.as_ref().unwrap_or(&self.effects).clone(),
-                    on_hit: modifier.on_hit.as_ref().unwrap_or(&self.on_hit).clone(),
-                    on_crit: modifier.on_crit.as_ref().unwrap_or(&self.on_crit).clone(),
-                    cost: modifier.cost.unwrap_or(self.cost),
-                    ..self.clone()
-                };
+fn resolve_targets(
+    world: &World,
+    caster: Entity,
+    targets: &[Entity],
+    effect_target: EffectTarget,
+) -> Vec<Entity> {
+    // Only the primary targets can include the dead, and only if the skill asked for them
+    let caster_hostile = world.satisfies::<&Hostile>(caster).unwrap_or(false);
+    let side = |allies: bool| {
+        world
+            .query::<Without<(&Health, Satisfies<&Hostile>), &Dead>>()
+            .iter()
+            .filter(|(_, (_, hostile))| (*hostile == caster_hostile) == allies)
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>()
+    };
+    match effect_target {
+        EffectTarget::Target => targets.to_vec(),
+        EffectTarget::Caster => vec![caster],
+        EffectTarget::Hostile => side(false),
+        EffectTarget::Friendly => side(true),
+        EffectTarget::OtherFriendly => side(true).into_iter().filter(|&e| e != caster).collect(),
+        EffectTarget::All => world
+            .query::<Without<&Health, &Dead>>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect(),
+    }
+}
+
+fn apply_buff(world: &mut World, target: Entity, buff: Buff, events: &mut Vec<CombatEvent>) {
+    let mut refreshed = false;
+    match buff {
+        Buff::Haste { duration } => {
+            refreshed = refresh(world, target, Hasted(duration));
+        }
+        Buff::Shell { duration } => {
+            refreshed = refresh(world, target, Shelled(duration));
+        }
+        Buff::Taunt { duration } => {
+            refreshed = refresh(world, target, Taunting(duration));
+        }
+        Buff::Ready {
+            skill,
+            trigger,
+            duration,
+        } => {
+            refreshed = refresh(
+                world,
+                target,
+                Readied {
+                    skill,
+                    trigger,
+                    turns: duration,
+                },
+            );
+        }
+        Buff::React {
+            name,
+            trigger,
+            response,
+        } => {
+            world
+                .insert_one(
+                    target,
+                    Reaction {
+                        name,
+                        trigger,
+                        response,
+                    },
+                )
+                .unwrap();
+        }
+        Buff::Barrier { amount } => {
+            let current = world.get::<&Barrier>(target).map_or(0, |barrier| barrier.0);
+            world.insert_one(target, Barrier(current + amount)).unwrap();
+        }
+        Buff::Revived => {
+            if world.remove_one::<Dead>(target).is_err() {
+                // Nothing to revive
+                return;
+            }
+            // Back in the turn order a turn after whoever is up now
+            let now = world
+                .query::<Without<&Initiative, &Dead>>()
+                .iter()
+                .filter(|&(e, _)| e != target)
+                .map(|(_, initiative)| initiative.0)
+                .fold(f32::INFINITY, f32::min);
+            let mut query = world
+                .query_one::<(&mut Health, &Stats, &mut Initiative)>(target)
+                .expect("Target not found");
+            if let Some((Health(health), stats, Initiative(initiative))) = query.get() {
+                *health = stats.max_health / 4;
+                if now.is_finite() {
+                    *initiative = now + 1. / stats.speed as f32;
+                }
             }
         }
+        Buff::Cleansed => {
+            let removed = DEBUFFS
+                .iter()
+                .filter_map(|debuff| (debuff.remove)(world, target))
+                .collect();
+            events.push(CombatEvent::Cleansed { target, removed });
+            return;
+        }
+    }
+    events.push(CombatEvent::BuffApplied {
+        target,
+        buff,
+        refreshed,
+    });
+}
+
+/// Sets off the first reaction among the caster's opponents that the skill triggers, using it up.
+fn react(
+    world: &mut World,
+    caster: Entity,
+    skill: &Skill,
+    targets: &[Entity],
+) -> Option<CombatEvent> {
+    let caster_hostile = world.satisfies::<&Hostile>(caster).unwrap_or(false);
+    let (reactor, name, response) = world
+        .query::<Without<(&Reaction, Satisfies<&Hostile>), &Dead>>()
+        .iter()
+        .find(|&(reactor, (reaction, hostile))| {
+            hostile != caster_hostile && (reaction.trigger)(skill, targets, reactor)
+        })
+        .map(|(reactor, (reaction, _))| (reactor, reaction.name, reaction.response))?;
+    world.remove_one::<Reaction>(reactor).unwrap();
+    Some(CombatEvent::Reacted {
+        reactor,
+        caster,
+        reaction: name,
+        skill: skill.name,
+        response,
+    })
+}
+
+/// Reapplying a status refreshes its duration rather than stacking it. Returns whether the target
+/// already had it.
+fn refresh<T: Status>(world: &mut World, target: Entity, mut status: T) -> bool {
+    let refreshed = if let Ok(mut current) = world.get::<&mut T>(target) {
+        let turns = status.turns();
+        *turns = (*turns).max(*current.turns());
+        true
+    } else {
+        false
+    };
+    world.insert_one(target, status).unwrap();
+    refreshed
+}
+
+fn apply_debuff(world: &mut World, target: Entity, debuff: Debuff, events: &mut Vec<CombatEvent>) {
+    if let Ok(mut immunities) = world.get::<&mut Immunities>(target) {
+        if immunities.contains(debuff.status_name()) {
+            events.push(CombatEvent::Immune {
+                target,
+                status: debuff.status_name(),
+            });
+            return;
+        }
+        if matches!(debuff, Debuff::Stunned { .. }) {
+            immunities.stunned();
+        }
+    }
+    let refreshed = match debuff {
+        Debuff::Burning { stacks, duration } => {
+            // New stacks add up to the cap, while the duration only ever gets refreshed
+            let (current_stacks, current_turns) = world
+                .get::<&Burning>(target)
+                .map_or((0, 0), |burning| (burning.stacks, burning.turns));
+            world
+                .insert_one(
+                    target,
+                    Burning {
+                        stacks: current_stacks
+                            .saturating_add(stacks)
+                            .min(MAX_BURNING_STACKS),
+                        turns: current_turns.max(duration),
+                    },
+                )
+                .unwrap();
+            current_turns > 0
+        }
+        Debuff::Frozen { amount } => refresh(world, target, Frozen(amount)),
+        Debuff::Contagious { duration } => refresh(world, target, Contagious(duration)),
+        Debuff::Zapped { duration } => refresh(world, target, Zapped(duration)),
+        Debuff::Regen { amount, duration } => refresh(world, target, Regen { amount, duration }),
+        Debuff::Stunned { duration } => refresh(world, target, Stunned(duration)),
+        Debuff::Slow { duration } => refresh(world, target, Slowed(duration)),
+        Debuff::Confused { duration } => refresh(world, target, Confused(duration)),
+    };
+    events.push(CombatEvent::DebuffApplied {
+        target,
+        debuff,
+        refreshed,
+    });
+}
+
+/// Delayed effects waiting to go off on this entity
+pub struct PendingEffects(Vec<Pending>);
+
+impl PendingEffects {
+    /// Turns until the next delayed effect goes off
+    pub fn countdown(&self) -> Option<u8> {
+        self.0.iter().map(|pending| pending.turns).min()
+    }
+}
+
+/// A delayed effect along with a snapshot of its caster from when it was cast
+#[derive(Clone)]
+struct Pending {
+    turns: u8,
+    skill: &'static str,
+    caster: Entity,
+    name: &'static str,
+    stats: Stats,
+    level: u8,
+    hostile: bool,
+    effects: Vec<Effect>,
+}
+
+impl Pending {
+    fn fire(
+        self,
+        world: &mut World,
+        target: Entity,
+        rng: &mut impl Rng,
+        events: &mut Vec<CombatEvent>,
+    ) {
+        events.push(CombatEvent::DelayedTriggered {
+            target,
+            skill: self.skill,
+        });
+        // Resolve with the stats from cast time, on a stand-in if the caster is gone
+        let live_stats = world.get::<&Stats>(self.caster).ok().map(|stats| *stats);
+        let caster = match live_stats {
+            Some(_) => {
+                world.insert_one(self.caster, self.stats).unwrap();
+                self.caster
+            }
+            None => {
+                let ghost = world.spawn((Name(self.name), self.stats, Level(self.level), Ghost));
+                if self.hostile {
+                    world.insert_one(ghost, Hostile).unwrap();
+                }
+                ghost
+            }
+        };
+        let targets = vec![target];
+        let skill = Skill::default();
+        for effect in self.effects.iter() {
+            skill.effect(effect, world, caster, &targets, false, rng, events);
+        }
+        if let Some(live_stats) = live_stats {
+            world.insert_one(caster, live_stats).unwrap();
+        }
+    }
+}
+
+/// Counts down the entity's delayed effects at the end of its turn, firing the ones that are due.
+pub fn tick_pending(
+    world: &mut World,
+    entity: Entity,
+    rng: &mut impl Rng,
+    events: &mut Vec<CombatEvent>,
+) {
+    let due = {
+        let Ok(mut queue) = world.get::<&mut PendingEffects>(entity) else {
+            return;
+        };
+        for pending in queue.0.iter_mut() {
+            pending.turns = pending.turns.saturating_sub(1);
+        }
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut queue.0)
+            .into_iter()
+            .partition(|pending| pending.turns == 0);
+        queue.0 = waiting;
+        due
+    };
+    if world
+        .get::<&PendingEffects>(entity)
+        .is_ok_and(|queue| queue.0.is_empty())
+    {
+        world.remove_one::<PendingEffects>(entity).unwrap();
+    }
+    // Fizzles if the target has died in the meantime
+    if world.satisfies::<&Dead>(entity).unwrap_or(true) {
+        return;
+    }
+    for pending in due {
+        pending.fire(world, entity, rng, events);
+    }
+}
+
+/// Burning deals fire damage per stack at the end of the burning entity's turn.
+pub fn burn(world: &mut World, entity: Entity, events: &mut Vec<CombatEvent>) {
+    let Ok(mut query) =
+        world.query_one::<(&Burning, &mut Health, &Stats, Option<&Resistances>)>(entity)
+    else {
+        return;
+    };
+    let Some((&Burning { stacks, .. }, Health(health), stats, resistances)) = query.get() else {
+        return;
+    };
+    let resistance = resistances.map_or(1., |r| r.get(DamageType::Fire));
+    let damage = ((stats.max_health / 20).max(1) * stacks as u32) as f32 * resistance;
+    let damage = damage as u32;
+    let before = *health;
+    *health = health.saturating_sub(damage);
+
+    events.push(CombatEvent::DamageDealt {
+        target: entity,
+        amount: before - *health,
+        crit: false,
+        damage_type: DamageType::Fire,
+        resistance,
+        absorbed: 0,
+        hit: None,
+    });
+    if *health == 0 {
+        events.push(CombatEvent::Killed { target: entity });
+    }
+}
+
+impl Skill {
+    fn builder(name: &'static str) -> SkillBuilder {
+        SkillBuilder::new(name)
+    }
+
+    /// The skill as cast on the target, or on the first of several targets.
+    fn get_modified(&self, caster: EntityRef, target: EntityRef) -> Skill {
+        if let Some(modifier) = self
+            .modifiers
+            .iter()
+            .find(|modifier| modifier.test.passes(caster, target))
+        {
+            return Skill {
+                modifiers: Vec::new(),
+                effects: modifier.effects.as_ref().unwrap_or(&self.effects).clone(),
+                on_hit: modifier.on_hit.as_ref().unwrap_or(&self.on_hit).clone(),
+                on_crit: modifier.on_crit.as_ref().unwrap_or(&self.on_crit).clone(),
+                on_miss: modifier.on_miss.as_ref().unwrap_or(&self.on_miss).clone(),
+                on_kill: modifier.on_kill.as_ref().unwrap_or(&self.on_kill).clone(),
+                cost: modifier.cost.as_ref().unwrap_or(&self.cost).clone(),
+                ..self.clone()
+            };
+        }
         self.clone()
     }
 
+    /// Whether the caster can afford the skill against at least some target. Use
+    /// `can_afford_against` once the target is known.
+    pub fn can_afford(&self, world: &World, caster: Entity) -> bool {
+        self.cost.can_afford(world, caster)
+            || self.modifiers.iter().any(|modifier| {
+                modifier
+                    .cost
+                    .as_ref()
+                    .is_some_and(|cost| cost.can_afford(world, caster))
+            })
+    }
+
+    pub fn can_afford_against(&self, world: &World, caster: Entity, target: Entity) -> bool {
+        let (Ok(caster_ref), Ok(target_ref)) = (world.entity(caster), world.entity(target)) else {
+            return false;
+        };
+        self.get_modified(caster_ref, target_ref)
+            .cost
+            .can_afford(world, caster)
+    }
+
+    /// Turns left before the caster can use this skill again, 0 when it's ready.
+    pub fn cooldown_left(&self, world: &World, caster: Entity) -> u8 {
+        world
+            .get::<&Cooldowns>(caster)
+            .ok()
+            .and_then(|cooldowns| cooldowns.0.get(self.name).copied())
+            .unwrap_or(0)
+    }
+
+    /// Who the skill can be used on, for the player
+    pub fn target_name(&self) -> &'static str {
+        match self.target {
+            PrimaryTarget::Caster => "Self",
+            PrimaryTarget::Hostile => "One enemy",
+            PrimaryTarget::AllHostile => "All enemies",
+            PrimaryTarget::Friendly => "One ally",
+            PrimaryTarget::AllFriendly => "All allies",
+            PrimaryTarget::DeadFriendly => "One fallen ally",
+            PrimaryTarget::Any => "Anyone",
+            PrimaryTarget::All => "Everyone",
+        }
+    }
+
+    /// Who the skill targets and what it costs, derived from the skill itself.
+    pub fn summary(&self) -> String {
+        let target = self.target_name();
+        let cost = &self.cost;
+        if self.cooldown > 0 {
+            format!("{target} · {cost} · CD {}", self.cooldown)
+        } else {
+            format!("{target} · {cost}")
+        }
+    }
+
+    pub fn charged(&self) -> bool {
+        self.charged
+    }
+
+    pub fn cost(&self) -> &Cost {
+        &self.cost
+    }
+
+    /// Skills meant for the caster's allies, like heals
+    pub fn targets_allies(&self) -> bool {
+        matches!(
+            self.target,
+            PrimaryTarget::Friendly | PrimaryTarget::AllFriendly
+        )
+    }
+
+    pub fn targets_caster(&self) -> bool {
+        matches!(self.target, PrimaryTarget::Caster)
+    }
+
+    /// Skills dealing physical damage that the back row blunts
+    pub fn melee(&self) -> bool {
+        self.effects.iter().any(|effect| {
+            matches!(effect, Effect::Damage(damage, _)
+                if matches!(damage.damage_type, DamageType::Physical) && !damage.ignores_rows)
+        })
+    }
+
     pub fn get_targets(&self, world: &World, caster: Entity) -> (Vec<Entity>, bool) {
         if matches!(self.target, PrimaryTarget::Caster) {
             return (vec![caster], false);
         }
+        // Sides are relative to the caster, so enemies target the party with the same skills
+        let caster_hostile = world.satisfies::<&Hostile>(caster).unwrap_or(false);
         (
             world
-                .query::<(Satisfies<&Party>, Satisfies<&Hostile>)>()
+                .query::<(&Health, Satisfies<&Hostile>, Satisfies<&Dead>)>()
                 .iter()
-                .filter_map(|(entity, (friendly, hostile))| match self.target {
-                    PrimaryTarget::Hostile | PrimaryTarget::AllHostile if friendly => None,
-                    PrimaryTarget::Friendly | PrimaryTarget::AllFriendly if hostile => None,
+                .map(|(entity, (_, hostile, dead))| (entity, hostile == caster_hostile, dead))
+                .filter_map(|(entity, ally, dead)| match self.target {
+                    PrimaryTarget::Hostile | PrimaryTarget::AllHostile if ally => None,
+                    PrimaryTarget::Friendly | PrimaryTarget::AllFriendly if !ally => None,
+                    PrimaryTarget::DeadFriendly if !ally || !dead => None,
+                    PrimaryTarget::DeadFriendly => Some(entity),
+                    _ if dead => None,
                     _ => Some(entity),
                 })
                 .collect(),
@@ -400,30 +1353,52 @@ impl Skill {
         )
     }
 
-    pub fn apply(&self, world: &mut World, caster: Entity, targets: &Vec<Entity>) {
+    pub fn apply(
+        &self,
+        world: &mut World,
+        caster: Entity,
+        targets: &Vec<Entity>,
+        rng: &mut impl Rng,
+    ) -> Vec<CombatEvent> {
+        let mut skill = self.get_modified(
+            world.entity(caster).expect("Caster must exist"),
+            world
+                .entity(targets.first().copied().unwrap_or(caster))
+                .expect("Target must exist"),
+        );
+        let mut events = vec![CombatEvent::SkillUsed {
+            caster,
+            skill: skill.name,
+        }];
+        let spent = skill.cost.pay(world, caster);
+        for effect in skill.effects.iter_mut() {
+            if let Effect::Damage(damage, _) = effect {
+                if damage.per_spent {
+                    damage.multiplier *= spent as f32;
+                }
+                if damage.hits_per_spent {
+                    damage.hits = spent.min(u8::MAX as u32) as u8;
+                }
+            }
+        }
+        if skill.cooldown > 0
+            && let Ok(mut cooldowns) = world.get::<&mut Cooldowns>(caster)
         {
-            let mut caster_query = world
-                .query_one::<(&Name, Satisfies<&Hostile>)>(caster)
-                .unwrap();
-            let (&Name(caster_name), hostile) = caster_query.get().unwrap();
-
-            let mut log = LOG.lock().unwrap();
-            log.write(Line::from(vec![
-                if hostile {
-                    caster_name.red()
-                } else {
-                    caster_name.green()
-                },
-                " uses ".into(),
-                self.name.blue(),
-            ]));
+            // The turn the skill is used on ticks it down once as well
+            cooldowns.0.insert(skill.name, skill.cooldown + 1);
         }
-        drain_resource(world, caster, self.cost);
-        for effect in self.effects.iter() {
-            self.effect(effect, world, caster, targets, true);
+        // Paid for either way, so a reaction costs the caster the whole skill
+        if let Some(reacted) = react(world, caster, &skill, targets) {
+            events.push(reacted);
+            return events;
         }
+        for effect in skill.effects.iter() {
+            skill.effect(effect, world, caster, targets, true, rng, &mut events);
+        }
+        events
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn effect(
         &self,
         effect: &Effect,
@@ -431,21 +1406,20 @@ impl Skill {
         caster: Entity,
         targets: &Vec<Entity>,
         on_hit: bool,
+        rng: &mut impl Rng,
+        events: &mut Vec<CombatEvent>,
     ) {
         match effect {
             Effect::Damage(effect_damage, effect_target) => {
-                let targets = match effect_target {
-                    EffectTarget::Target => targets,
-                    EffectTarget::Caster => &vec![caster],
-                    EffectTarget::Hostile => {
-                        &world.query::<&Hostile>().iter().map(|(e, _)| e).collect()
-                    }
-                    EffectTarget::Friendly => {
-                        &world.query::<&Party>().iter().map(|(e, _)| e).collect()
-                    }
-                    EffectTarget::All => &world.query::<&Health>().iter().map(|(e, _)| e).collect(),
+                let targets = &resolve_targets(world, caster, targets, *effect_target);
+                // Heals can keep landing on anyone, but hits stop landing on the dead
+                let healing = matches!(effect_damage.damage_type, DamageType::Healing);
+                let standing = |world: &World, target: Entity| {
+                    healing
+                        || world
+                            .get::<&Health>(target)
+                            .is_ok_and(|health| health.0 > 0)
                 };
-                let mut target_iter = targets.iter().cycle();
 
                 let hits = if effect_damage.randomized {
                     // If randomized, hits is the total number of random hits
@@ -455,17 +1429,55 @@ impl Skill {
                     effect_damage.hits * targets.len() as u8
                 };
 
-                let mut rng = rand::rng();
+                // Numbered up front by who each hit was meant for, so the numbers don't shift when
+                // a target dies and its hits carry over
+                let per_target = if effect_damage.randomized {
+                    hits
+                } else {
+                    effect_damage.hits
+                };
+                let labels = (0..hits)
+                    .map(|hit| {
+                        let round = if effect_damage.randomized {
+                            hit
+                        } else {
+                            hit / targets.len() as u8
+                        };
+                        (per_target > 1).then_some((round + 1, per_target))
+                    })
+                    .collect::<Vec<_>>();
+                let mut total = 0;
 
-                for _ in 0..hits {
+                for hit in 0..hits {
                     let target = if effect_damage.randomized {
-                        // len inclusive lets us hit the same target multiple times
-                        target_iter.nth(rng.random_range(..=targets.len()))
+                        // Each hit picks again from whoever is still standing
+                        targets
+                            .iter()
+                            .copied()
+                            .filter(|&target| standing(world, target))
+                            .collect::<Vec<_>>()
+                            .choose(rng)
+                            .copied()
                     } else {
-                        target_iter.next()
+                        let planned = targets[hit as usize % targets.len()];
+                        if standing(world, planned) {
+                            Some(planned)
+                        } else {
+                            // Overkill carries over to the next target still standing
+                            let next = (1..targets.len())
+                                .map(|offset| targets[(hit as usize + offset) % targets.len()])
+                                .find(|&target| standing(world, target));
+                            if let Some(next) = next {
+                                events.push(CombatEvent::Redirected {
+                                    from: planned,
+                                    to: next,
+                                });
+                            }
+                            next
+                        }
                     };
 
-                    let Some(&target) = target else {
+                    let Some(target) = target else {
                         break;
                     };
 
@@ -476,118 +1488,1067 @@ impl Skill {
                     };
 
                     let mut on_crit = false;
+                    let mut immune = false;
+                    let mut dodged = false;
+                    let mut killed = false;
+                    let mut stolen = 0;
+                    let mut recharged = 0;
+                    let mut dealt = 0;
 
                     {
+                        let caster_level = world.get::<&Level>(caster).map_or(0, |l| l.0);
                         let caster_stats = world
                             .get::<&Stats>(caster)
                             .expect("Can't cast skills without a Stats component!");
+                        let defending = world.satisfies::<&Defending>(target).unwrap_or(false);
+                        // Each end of the swing standing in the back row softens it
+                        let back_rows = [caster, target]
+                            .into_iter()
+                            .filter(|&entity| {
+                                world.get::<&Row>(entity).is_ok_and(|row| *row == Row::Back)
+                            })
+                            .count();
                         let mut target_query = world
-                            .query_one::<(&mut Health, &Stats, &Name, Satisfies<&Hostile>)>(target)
+                            .query_one::<(
+                                &mut Health,
+                                &Stats,
+                                Satisfies<&Shelled>,
+                                Option<&Resistances>,
+                                Option<&mut Barrier>,
+                            )>(target)
                             .expect("Target not found");
-                        let (Health(target_health), target_stats, Name(target_name), hostile) =
+                        let (Health(target_health), target_stats, shelled, resistances, barrier) =
                             target_query
                                 .get()
                                 .expect("Can't be a target without stats and health");
 
+                        let mut hit_chance = caster_stats.accuracy - target_stats.evade;
+                        if world.satisfies::<&Blind>(caster).unwrap_or(false) {
+                            hit_chance -= 0.5;
+                        }
+
                         if matches!(effect_damage.damage_type, DamageType::Healing) {
-                            let damage = target_stats.max_health as f32 * effect_damage.multiplier;
+                            let heal = target_stats.max_health as f32 * effect_damage.multiplier;
+                            let before = *target_health;
                             *target_health =
-                                (*target_health + damage as u32).min(target_stats.max_health);
-
-                            let mut log = LOG.lock().unwrap();
-                            log.write(
-                                Line::from(vec![
-                                    if hostile {
-                                        target_name.red()
-                                    } else {
-                                        target_name.green()
-                                    },
-                                    " gains ".into(),
-                                    format!("{damage} ").bold(),
-                                    Span::from(effect_damage.damage_type).content("Health"),
-                                ])
-                                .right_aligned(),
-                            );
+                                (*target_health + heal.round() as u32).min(target_stats.max_health);
+                            events.push(CombatEvent::Healed {
+                                target,
+                                amount: *target_health - before,
+                            });
+                        } else if target != caster && hit_chance <= rng.random() {
+                            dodged = true;
+                            events.push(CombatEvent::Missed { target });
                         } else {
-                            let mut damage = caster_stats.attack as f32;
-                            damage *= (caster_stats.attack as f32 / target_stats.defense as f32)
-                                .clamp(0.5, 1.);
+                            let mut damage = match effect_damage.base {
+                                Some(base) => base as f32,
+                                None => {
+                                    caster_stats.attack as f32
+                                        * (caster_stats.attack as f32 / target_stats.defense as f32)
+                                            .clamp(0.5, 1.)
+                                }
+                            };
                             damage *= effect_damage.multiplier;
+                            damage *= 1. + effect_damage.scaling * caster_level as f32;
+                            damage *= 1.
+                                + rng
+                                    .random_range(-effect_damage.variance..=effect_damage.variance);
                             if caster_stats.crit > rng.random() {
-                                damage *= effect_damage.crit_multiplier;
+                                damage *= effect_damage.crit_multiplier * caster_stats.crit_damage
+                                    / BASE_CRIT_DAMAGE;
                                 on_crit = true;
                             }
+                            if shelled && matches!(effect_damage.damage_type, DamageType::Physical)
+                            {
+                                damage *= 0.5;
+                            }
+                            let resistance =
+                                resistances.map_or(1., |r| r.get(effect_damage.damage_type));
+                            damage *= resistance;
+                            immune = resistance == 0.;
+                            if defending {
+                                damage *= 0.5;
+                            }
+                            if matches!(effect_damage.damage_type, DamageType::Physical)
+                                && !effect_damage.ignores_rows
+                            {
+                                damage *= BACK_ROW_DAMAGE.powi(back_rows as i32);
+                            }
+
+                            // Round once so the logged number matches the health lost
+                            let damage = damage.round() as u32;
+                            let absorbed = barrier.map_or(0, |Barrier(barrier)| {
+                                let absorbed = damage.min(*barrier);
+                                *barrier -= absorbed;
+                                absorbed
+                            });
+                            let before = *target_health;
+                            *target_health = target_health.saturating_sub(damage - absorbed);
+                            dealt = before - *target_health;
 
-                            *target_health = target_health.saturating_sub(damage as u32);
-
-                            let mut log = LOG.lock().unwrap();
-                            log.write(
-                                Line::from(vec![
-                                    if hostile {
-                                        target_name.red()
-                                    } else {
-                                        target_name.green()
-                                    },
-                                    " takes ".into(),
-                                    format!("{damage}").bold(),
-                                    if on_crit {
-                                        " critical ".bold()
-                                    } else {
-                                        " ".into()
-                                    },
-                                    effect_damage.damage_type.into(),
-                                    " damage".into(),
-                                ])
-                                .right_aligned(),
-                            );
+                            // Overkill isn't logged, only what the target actually lost
+                            total += absorbed + dealt;
+                            events.push(CombatEvent::DamageDealt {
+                                target,
+                                amount: absorbed + dealt,
+                                crit: on_crit,
+                                damage_type: effect_damage.damage_type,
+                                resistance,
+                                absorbed,
+                                hit: labels[hit as usize],
+                            });
+                            if before > 0 && *target_health == 0 {
+                                killed = true;
+                                events.push(CombatEvent::Killed { target });
+                            }
+                            stolen = (dealt as f32 * effect_damage.lifesteal).round() as u32;
+                            recharged = (dealt as f32 * effect_damage.recharge).round() as u8;
+                        }
+                    }
+
+                    if world
+                        .get::<&Barrier>(target)
+                        .is_ok_and(|barrier| barrier.0 == 0)
+                    {
+                        world.remove_one::<Barrier>(target).unwrap();
+                    }
+
+                    // Healed after the target's health is released in case they share an archetype
+                    if stolen > 0 {
+                        let mut caster_query = world
+                            .query_one::<(&mut Health, &Stats)>(caster)
+                            .expect("Caster not found");
+                        if let Some((Health(health), stats)) = caster_query.get() {
+                            let before = *health;
+                            *health = (*health + stolen).min(stats.max_health);
+                            events.push(CombatEvent::Healed {
+                                target: caster,
+                                amount: *health - before,
+                            });
                         }
                     }
 
-                    if on_hit {
+                    // Enemies remember who hurt them
+                    if dealt > 0
+                        && world.satisfies::<&Hostile>(target).unwrap_or(false)
+                        && let Ok(mut threat) = world.get::<&mut Threat>(caster)
+                    {
+                        threat.0 += dealt as f32;
+                    }
+
+                    if recharged > 0 {
+                        gain_resource(world, caster, Job::Nanovampire { battery: recharged });
+                    }
+
+                    if on_hit && dodged {
+                        let targets = vec![target];
+                        for effect in self.on_miss.iter() {
+                            self.effect(effect, world, caster, &targets, false, rng, events);
+                        }
+                    }
+
+                    // Immune targets weren't really hit
+                    if on_hit && !immune && !dodged {
                         let targets = vec![target];
                         for effect in self.on_hit.iter() {
-                            self.effect(&effect, world, caster, &targets, false);
+                            self.effect(effect, world, caster, &targets, false, rng, events);
                         }
                         if on_crit {
                             for effect in self.on_crit.iter() {
-                                self.effect(&effect, world, caster, &targets, false);
+                                self.effect(effect, world, caster, &targets, false, rng, events);
+                            }
+                        }
+                        // The target isn't despawned until check_dead, so it's still readable
+                        if killed {
+                            for effect in self.on_kill.iter() {
+                                self.effect(effect, world, caster, &targets, false, rng, events);
                             }
                         }
                     }
                 }
+
+                if per_target > 1 && hits > 2 {
+                    events.push(CombatEvent::DamageTotal {
+                        amount: total,
+                        hits,
+                    });
+                }
             }
-            Effect::Conditional(TestFn(test), effects) => {
-                for target in targets.iter() {
-                    let caster_ref = world.entity(caster).expect("Caster not found");
-                    let target_ref = world.entity(*target).expect("Target not found");
-                    if test(caster_ref, target_ref) {
-                        for effect in effects.iter() {
-                            self.effect(effect, world, caster, targets, on_hit);
-                        }
+            Effect::Summon(templates) => {
+                let hostile = world.satisfies::<&Hostile>(caster).unwrap_or(false);
+                let summoned = encounters::spawn_templates(world, templates, rng);
+                for &summon in &summoned {
+                    world.insert_one(summon, Summoned).unwrap();
+                    // Templates are hostile by default
+                    if !hostile {
+                        world.remove::<(Hostile, XpReward)>(summon).unwrap();
+                        // Jobless, but still a party member as far as the party panel goes
+                        world.insert(summon, (Party, Job::None)).unwrap();
                     }
                 }
+
+                // Summons act one of their own turns after the caster's current one
+                let initiative = world.get::<&Initiative>(caster).map_or(0., |i| i.0);
+                for summon in summoned {
+                    let speed = world.get::<&Stats>(summon).map_or(1, |stats| stats.speed);
+                    world
+                        .insert_one(summon, Initiative(initiative + 1. / speed as f32))
+                        .unwrap();
+                    events.push(CombatEvent::Summoned { target: summon });
+                }
+            }
+            Effect::Scan { resistances } => {
+                for &target in targets {
+                    let unscannable = world.satisfies::<&Unscannable>(target).unwrap_or(false);
+                    // A plain scan doesn't undo what a deep one already revealed
+                    let revealed = world
+                        .get::<&Scanned>(target)
+                        .is_ok_and(|scanned| scanned.resistances);
+                    world
+                        .insert_one(
+                            target,
+                            Scanned {
+                                resistances: (*resistances && !unscannable) || revealed,
+                            },
+                        )
+                        .unwrap();
+                    events.push(CombatEvent::Scanned {
+                        target,
+                        resistances: *resistances,
+                        hidden: unscannable,
+                    });
+                }
+            }
+            Effect::Random(choices) => {
+                let Ok((_, chosen)) = choices.choose_weighted(rng, |&(weight, _)| weight as u32)
+                else {
+                    return;
+                };
+                events.push(CombatEvent::Rolled {
+                    skill: self.name,
+                    outcome: chosen.describe(),
+                });
+                self.effect(chosen, world, caster, targets, on_hit, rng, events);
+            }
+            Effect::Delayed { turns, effects } => {
+                let pending = Pending {
+                    turns: *turns,
+                    skill: self.name,
+                    caster,
+                    name: world.get::<&Name>(caster).expect("Caster needs a name").0,
+                    stats: *world.get::<&Stats>(caster).expect("Caster needs stats"),
+                    level: world.get::<&Level>(caster).map_or(0, |level| level.0),
+                    hostile: world.satisfies::<&Hostile>(caster).unwrap_or(false),
+                    effects: effects.clone(),
+                };
+                for &target in targets.iter() {
+                    if let Ok(mut queue) = world.get::<&mut PendingEffects>(target) {
+                        queue.0.push(pending.clone());
+                    } else {
+                        world
+                            .insert_one(target, PendingEffects(vec![pending.clone()]))
+                            .unwrap();
+                    }
+                    events.push(CombatEvent::DelayedApplied {
+                        target,
+                        skill: self.name,
+                        turns: *turns,
+                    });
+                }
+            }
+            Effect::Conditional(test, effects) => {
+                // Only the targets that pass get the sub-effects, and each of them only once
+                let passed = targets
+                    .iter()
+                    .copied()
+                    .filter(|&target| {
+                        let caster_ref = world.entity(caster).expect("Caster not found");
+                        let target_ref = world.entity(target).expect("Target not found");
+                        test.passes(caster_ref, target_ref)
+                    })
+                    .collect::<Vec<_>>();
+                if !passed.is_empty() {
+                    for effect in effects.iter() {
+                        self.effect(effect, world, caster, &passed, on_hit, rng, events);
+                    }
+                }
+            }
+            Effect::Chance(chance, effects) => {
+                let lucky = targets
+                    .iter()
+                    .copied()
+                    .filter(|_| rng.random_bool(*chance))
+                    .collect::<Vec<_>>();
+                if !lucky.is_empty() {
+                    for effect in effects.iter() {
+                        self.effect(effect, world, caster, &lucky, on_hit, rng, events);
+                    }
+                }
+            }
+            Effect::Buff(buff, effect_target) => {
+                for target in resolve_targets(world, caster, targets, *effect_target) {
+                    apply_buff(world, target, *buff, events);
+                }
+            }
+            Effect::Debuff(debuff, effect_target) => {
+                for target in resolve_targets(world, caster, targets, *effect_target) {
+                    apply_debuff(world, target, *debuff, events);
+                }
             }
             Effect::Gain(amount) => gain_resource(world, caster, *amount),
             Effect::Drain(amount) => drain_resource(world, caster, *amount),
-            _ => (),
         }
     }
 }
 
+struct SkillBuilder {
+    skill: Skill,
+}
+
+impl SkillBuilder {
+    fn new(name: &'static str) -> Self {
+        Self {
+            skill: Skill {
+                name,
+                effects: vec![],
+                ..Default::default()
+            },
+        }
+    }
+
+    fn description(mut self, description: &'static str) -> Self {
+        self.skill.description = description;
+        self
+    }
+
+    fn target(mut self, target: PrimaryTarget) -> Self {
+        self.skill.target = target;
+        self
+    }
+
+    fn effect(mut self, effect: Effect) -> Self {
+        self.skill.effects.push(effect);
+        self
+    }
+
+    #[allow(dead_code)]
+    fn on_hit(mut self, effect: Effect) -> Self {
+        self.skill.on_hit.push(effect);
+        self
+    }
+
+    fn on_crit(mut self, effect: Effect) -> Self {
+        self.skill.on_crit.push(effect);
+        self
+    }
+
+    fn on_miss(mut self, effect: Effect) -> Self {
+        self.skill.on_miss.push(effect);
+        self
+    }
+
+    fn on_kill(mut self, effect: Effect) -> Self {
+        self.skill.on_kill.push(effect);
+        self
+    }
+
+    fn cost(mut self, cost: impl Into<Cost>) -> Self {
+        self.skill.cost = cost.into();
+        self
+    }
+
+    fn charged(mut self) -> Self {
+        self.skill.charged = true;
+        self
+    }
+
+    fn cooldown(mut self, cooldown: u8) -> Self {
+        self.skill.cooldown = cooldown;
+        self
+    }
+
+    fn modifier(mut self, modifier: SkillModifier) -> Self {
+        self.skill.modifiers.push(modifier);
+        self
+    }
+
+    fn build(self) -> Skill {
+        self.skill
+    }
+}
+
 impl Default for Skill {
     fn default() -> Self {
         Self {
             name: "Uknown Skill",
+            description: "",
+            cooldown: 0,
+            charged: false,
             target: PrimaryTarget::Any,
             effects: vec![Effect::damage().build()],
             on_hit: vec![],
             on_crit: vec![],
-            cost: Job::None,
-            modifier: None,
+            on_miss: vec![],
+            on_kill: vec![],
+            cost: Cost::default(),
+            modifiers: Vec::new(),
         }
     }
 }
 
-fn is_burning(_caster: EntityRef, target: EntityRef) -> bool {
-    target.satisfies::<&Burning>()
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+    use ratatui::style::Color;
+
+    use super::*;
+
+    fn fighter(world: &mut World, attack: u32) -> Entity {
+        world.spawn((
+            Health(1000),
+            Stats {
+                max_health: 1000,
+                attack,
+                defense: attack,
+                ..Default::default()
+            },
+        ))
+    }
+
+    fn damage_dealt(events: &[CombatEvent]) -> u32 {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                CombatEvent::DamageDealt { amount, .. } => Some(amount),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Damage the skill deals to the target, rolled with the same seed every time
+    fn hit(world: &mut World, skill: &Skill, caster: Entity, target: Entity) -> u32 {
+        let events = skill.apply(world, caster, &vec![target], &mut StdRng::seed_from_u64(1));
+        damage_dealt(&events)
+    }
+
+    #[test]
+    fn shell_halves_physical_damage() {
+        let hit = |shelled: bool| {
+            let mut world = World::new();
+            let caster = fighter(&mut world, 20);
+            let target = fighter(&mut world, 20);
+            if shelled {
+                world.insert_one(target, Shelled(1)).unwrap();
+            }
+            hit(&mut world, &common::BASIC_ATTACK, caster, target)
+        };
+        // Same seed both times, and one that rolls an even hit so halving it doesn't round
+        let unshelled = hit(false);
+        assert_eq!(unshelled % 2, 0);
+        assert_eq!(hit(true) * 2, unshelled);
+    }
+
+    #[test]
+    fn hostile_summons_scale_with_the_party_and_are_worth_xp() {
+        let mut world = World::new();
+        // Has the XP to level up, which is for the end of the fight, not a summon
+        let member = world.spawn((Party, Level(5), crate::app::Xp(u32::MAX)));
+        let caster = fighter(&mut world, 20);
+        world.insert_one(caster, Hostile).unwrap();
+        let events = enemies::CALL_REINFORCEMENTS.apply(
+            &mut world,
+            caster,
+            &vec![caster],
+            &mut StdRng::seed_from_u64(1),
+        );
+        let drone = events
+            .iter()
+            .find_map(|event| match event {
+                CombatEvent::Summoned { target } => Some(*target),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(world.get::<&Level>(drone).unwrap().0, 4);
+        assert_eq!(world.get::<&XpReward>(drone).unwrap().0, 20 * 4);
+        assert_eq!(world.get::<&Stats>(drone).unwrap().max_health, 80);
+        assert_eq!(world.get::<&Level>(member).unwrap().0, 5);
+    }
+
+    #[test]
+    fn burning_bonus_stops_once_burning_expires() {
+        let mut world = World::new();
+        let caster = fighter(&mut world, 20);
+        let target = fighter(&mut world, 20);
+        let unburnt = hit(&mut world, &gunslinger::DOUBLE_TAP, caster, target);
+
+        world
+            .insert_one(
+                target,
+                Burning {
+                    stacks: 1,
+                    turns: 1,
+                },
+            )
+            .unwrap();
+        let burning = hit(&mut world, &gunslinger::DOUBLE_TAP, caster, target);
+        assert!(burning > unburnt);
+
+        let mut events = Vec::new();
+        burn(&mut world, target, &mut events);
+        crate::app::tick_statuses(&mut world, target, &[], &mut events);
+        assert!(!world.satisfies::<&Burning>(target).unwrap());
+        assert_eq!(
+            hit(&mut world, &gunslinger::DOUBLE_TAP, caster, target),
+            unburnt
+        );
+    }
+
+    #[test]
+    fn immunity_is_not_a_hit() {
+        let skill = Skill::builder("Searing Jab")
+            .target(PrimaryTarget::Hostile)
+            .effect(Effect::damage_type(DamageType::Fire).build())
+            .on_hit(Effect::Debuff(
+                Debuff::Stunned { duration: 1 },
+                EffectTarget::Target,
+            ))
+            .build();
+        let mut world = World::new();
+        let caster = fighter(&mut world, 20);
+        let vulnerable = fighter(&mut world, 20);
+        let immune = fighter(&mut world, 20);
+        world
+            .insert_one(immune, Resistances::default().with(DamageType::Fire, 0.))
+            .unwrap();
+
+        hit(&mut world, &skill, caster, vulnerable);
+        assert!(world.satisfies::<&Stunned>(vulnerable).unwrap());
+        assert_eq!(hit(&mut world, &skill, caster, immune), 0);
+        assert!(!world.satisfies::<&Stunned>(immune).unwrap());
+    }
+
+    #[test]
+    fn on_miss_and_on_hit_only_go_off_when_they_should() {
+        let skill = Skill::builder("Wild Swing")
+            .target(PrimaryTarget::Hostile)
+            .effect(Effect::damage().build())
+            .on_hit(Effect::Debuff(
+                Debuff::Stunned { duration: 1 },
+                EffectTarget::Target,
+            ))
+            .on_miss(Effect::Buff(
+                Buff::Haste { duration: 1 },
+                EffectTarget::Caster,
+            ))
+            .build();
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut hits, mut misses) = (0, 0);
+        for _ in 0..100 {
+            let mut world = World::new();
+            let caster = fighter(&mut world, 20);
+            let target = fighter(&mut world, 20);
+            world.get::<&mut Stats>(target).unwrap().evade = 0.5;
+            let events = skill.apply(&mut world, caster, &vec![target], &mut rng);
+            let missed = events
+                .iter()
+                .any(|event| matches!(event, CombatEvent::Missed { .. }));
+            assert_eq!(world.satisfies::<&Hasted>(caster).unwrap(), missed);
+            assert_eq!(world.satisfies::<&Stunned>(target).unwrap(), !missed);
+            if missed {
+                misses += 1;
+            } else {
+                hits += 1;
+            }
+        }
+        assert!(hits > 0 && misses > 0);
+    }
+
+    #[test]
+    fn heat_costs_add_heat_up_to_overheating() {
+        let mut world = World::new();
+        let netrunner = world.spawn((Job::Netrunner { ram: 8, heat: 70 },));
+        let cost = Cost::from(Job::Netrunner { ram: 2, heat: 30 });
+        assert!(cost.can_afford(&world, netrunner));
+        cost.pay(&mut world, netrunner);
+        assert!(matches!(
+            *world.get::<&Job>(netrunner).unwrap(),
+            Job::Netrunner { ram: 6, heat: 100 }
+        ));
+        assert!(!cost.can_afford(&world, netrunner));
+    }
+
+    #[test]
+    fn called_shot_refunds_its_rounds_on_a_miss() {
+        let ammo_left = |evade: f32| {
+            let mut world = World::new();
+            let caster = fighter(&mut world, 20);
+            world
+                .insert_one(caster, Job::Gunslinger { ammo: 4 })
+                .unwrap();
+            let target = fighter(&mut world, 20);
+            world.get::<&mut Stats>(target).unwrap().evade = evade;
+            hit(&mut world, &gunslinger::CALLED_SHOT, caster, target);
+            match *world.get::<&Job>(caster).unwrap() {
+                Job::Gunslinger { ammo } => ammo,
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(ammo_left(0.), 2);
+        assert_eq!(ammo_left(1.), 4);
+    }
+
+    #[test]
+    fn on_kill_goes_off_on_the_killing_hit_only() {
+        let battery = |world: &World, entity: Entity| match *world.get::<&Job>(entity).unwrap() {
+            Job::Nanovampire { battery } => battery,
+            _ => unreachable!(),
+        };
+        let exsanguinate = |health: u32| {
+            let mut world = World::new();
+            let caster = fighter(&mut world, 20);
+            world
+                .insert_one(caster, Job::Nanovampire { battery: 50 })
+                .unwrap();
+            let target = fighter(&mut world, 20);
+            world.get::<&mut Health>(target).unwrap().0 = health;
+            hit(&mut world, &nanovampire::EXSANGUINATE, caster, target);
+            battery(&world, caster)
+        };
+        assert_eq!(exsanguinate(1000), 20);
+        assert_eq!(exsanguinate(1), 50);
+
+        // Three hits on a target that only the second one kills, with a refund that has to read
+        // the dead target's health to go off
+        let skill = Skill::builder("Flurry")
+            .target(PrimaryTarget::Hostile)
+            .effect(Effect::damage().hits(3).build())
+            .on_kill(Effect::Conditional(
+                TestFn::Is(is_below_quarter_health),
+                vec![Effect::Gain(Job::Nanovampire { battery: 10 })],
+            ))
+            .build();
+        let mut world = World::new();
+        let caster = fighter(&mut world, 20);
+        world
+            .insert_one(caster, Job::Nanovampire { battery: 0 })
+            .unwrap();
+        let target = fighter(&mut world, 20);
+        let first = hit(&mut world, &common::BASIC_ATTACK, caster, target);
+        world.get::<&mut Health>(target).unwrap().0 = first + 1;
+        let events = skill.apply(
+            &mut world,
+            caster,
+            &vec![target],
+            &mut StdRng::seed_from_u64(1),
+        );
+        let count = |matches: fn(&CombatEvent) -> bool| {
+            events.iter().filter(|&event| matches(event)).count()
+        };
+        assert_eq!(count(|e| matches!(e, CombatEvent::DamageDealt { .. })), 2);
+        assert_eq!(count(|e| matches!(e, CombatEvent::Killed { .. })), 1);
+        assert_eq!(battery(&world, caster), 10);
+    }
+
+    #[test]
+    fn logged_damage_is_the_health_lost() {
+        let mut world = World::new();
+        let caster = fighter(&mut world, 20);
+        let target = fighter(&mut world, 20);
+        world.get::<&mut Health>(target).unwrap().0 = 5;
+        assert_eq!(hit(&mut world, &common::BASIC_ATTACK, caster, target), 5);
+        assert_eq!(world.get::<&Health>(target).unwrap().0, 0);
+    }
+
+    #[test]
+    fn logged_healing_is_the_health_gained() {
+        let mut world = World::new();
+        let target = fighter(&mut world, 20);
+        world.get::<&mut Health>(target).unwrap().0 = 990;
+        let events = common::POTION.apply(
+            &mut world,
+            target,
+            &vec![target],
+            &mut StdRng::seed_from_u64(1),
+        );
+        assert!(matches!(
+            events[..],
+            [_, CombatEvent::Healed { amount: 10, .. }]
+        ));
+        assert_eq!(world.get::<&Health>(target).unwrap().0, 1000);
+    }
+
+    #[test]
+    fn drain_heals_for_half_the_damage() {
+        let mut world = World::new();
+        let caster = fighter(&mut world, 20);
+        let target = fighter(&mut world, 20);
+        world.get::<&mut Health>(caster).unwrap().0 = 500;
+        // Rolls an even hit, so half of it doesn't round
+        let dealt = hit(&mut world, &nanovampire::DRAIN, caster, target);
+        assert_eq!(dealt % 2, 0);
+        assert_eq!(world.get::<&Health>(caster).unwrap().0, 500 + dealt / 2);
+    }
+
+    #[test]
+    fn hit_labels_hold_when_a_target_dies_mid_skill() {
+        let skill = Skill::builder("Twin Volley")
+            .target(PrimaryTarget::AllHostile)
+            .effect(Effect::damage().hits(2).build())
+            .build();
+        let mut world = World::new();
+        let caster = fighter(&mut world, 20);
+        let frail = fighter(&mut world, 20);
+        let sturdy = fighter(&mut world, 20);
+        for target in [frail, sturdy] {
+            world.insert(target, (Name("Sewer Rat"), Hostile)).unwrap();
+        }
+        world.get::<&mut Health>(frail).unwrap().0 = 1;
+        let events = skill.apply(
+            &mut world,
+            caster,
+            &vec![frail, sturdy],
+            &mut StdRng::seed_from_u64(1),
+        );
+        let lines = events
+            .iter()
+            .filter(|event| matches!(event, CombatEvent::DamageDealt { .. }))
+            .map(|event| {
+                let line = event.line(&world).unwrap();
+                let text = line.spans.iter().map(|span| span.content.as_ref());
+                text.collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].contains("Sewer Rat (1)") && lines[0].ends_with("(hit 1/2)"));
+        assert!(lines[1].contains("Sewer Rat (2)") && lines[1].ends_with("(hit 1/2)"));
+        assert!(lines[2].contains("Sewer Rat (2)") && lines[2].ends_with("(hit 2/2)"));
+        assert!(lines[3].contains("Sewer Rat (2)") && lines[3].ends_with("(hit 2/2)"));
+    }
+
+    #[test]
+    fn scaling_grows_with_the_caster_level() {
+        let skill = Skill::builder("Haymaker")
+            .target(PrimaryTarget::Hostile)
+            .effect(Effect::damage().scaling(0.05).build())
+            .build();
+        let hit_at = |level: u8| {
+            let mut world = World::new();
+            let caster = fighter(&mut world, 20);
+            let target = fighter(&mut world, 20);
+            world.insert_one(caster, Level(level)).unwrap();
+            hit(&mut world, &skill, caster, target)
+        };
+        let (novice, veteran) = (hit_at(1), hit_at(5));
+        // Same attack and the same roll, so only the 5% per level tells them apart
+        let expected = novice as f32 * 1.25 / 1.05;
+        assert!(
+            (veteran as f32 - expected).abs() <= 1.,
+            "{veteran} vs {expected}"
+        );
+        assert!(veteran > novice);
+    }
+
+    #[test]
+    fn skills_unlock_with_level() {
+        let names = |job: &Job, level: u8| {
+            skills_for(job, level)
+                .into_iter()
+                .map(|skill| skill.name)
+                .collect::<Vec<_>>()
+        };
+        let gunslinger = Job::Gunslinger { ammo: 0 };
+        assert_eq!(names(&gunslinger, 1), ["Double Tap"]);
+        assert_eq!(
+            names(&gunslinger, 5),
+            [
+                "Double Tap",
+                "Reload",
+                "Pistol Whip",
+                "Tactical Reload",
+                "Deadeye",
+                "Called Shot",
+                "Fan the Hammer",
+                "Draw Fire",
+                "Overwatch",
+                "Jackpot",
+            ]
+        );
+        let netrunner = Job::Netrunner { ram: 0, heat: 0 };
+        assert_eq!(names(&netrunner, 1), ["Short Circuit"]);
+        assert_eq!(
+            names(&netrunner, 5),
+            ["Short Circuit", "Vent", "Deep Scan", "Firewall", "Botnet"]
+        );
+        let technopriest = Job::Technopriest { prayers: 0 };
+        assert_eq!(names(&technopriest, 1), ["Mend"]);
+        assert_eq!(
+            names(&technopriest, 5),
+            ["Mend", "Aegis", "Benediction", "Deploy Turret", "Litany"]
+        );
+        let clairvoyant = Job::Clairvoyant { sun: 0, moon: 0 };
+        assert_eq!(names(&clairvoyant, 1), ["Sunbeam"]);
+        assert_eq!(
+            names(&clairvoyant, 5),
+            [
+                "Sunbeam",
+                "Moonshade",
+                "Premonition",
+                "Alignment",
+                "Doom",
+                "Gaze Into Tomorrow",
+                "Supernova",
+            ]
+        );
+        let nanovampire = Job::Nanovampire { battery: 0 };
+        assert_eq!(names(&nanovampire, 1), ["Drain"]);
+        assert_eq!(
+            names(&nanovampire, 5),
+            [
+                "Drain",
+                "Siphon",
+                "Exsanguinate",
+                "Static Discharge",
+                "Overclock",
+                "Transfusion",
+                "Blood Surge",
+            ]
+        );
+        assert!(skills_for(&Job::None, 5).is_empty());
+    }
+
+    #[test]
+    fn passing_modifiers_stack() {
+        let Effect::Damage(damage, _) = Effect::damage()
+            .modifier(DamageModifier {
+                test: TestFn::Is(is_burning),
+                multiplier: Some(2.),
+                ..Default::default()
+            })
+            .modifier(DamageModifier {
+                test: TestFn::Is(target_is_frozen),
+                damage_type: Some(DamageType::Fire),
+                multiplier: Some(1.5),
+                ..Default::default()
+            })
+            .build()
+        else {
+            unreachable!()
+        };
+        let mut world = World::new();
+        let caster = world.spawn((Health(1),));
+        let both = world.spawn((
+            Burning {
+                stacks: 1,
+                turns: 1,
+            },
+            Frozen(1),
+        ));
+        let burning = world.spawn((Burning {
+            stacks: 1,
+            turns: 1,
+        },));
+        let frozen = world.spawn((Frozen(1),));
+        let neither = world.spawn((Health(1),));
+        for (target, multiplier, fire) in [
+            (both, 3., true),
+            (burning, 2., false),
+            (frozen, 1.5, true),
+            (neither, 1., false),
+        ] {
+            let modified =
+                damage.get_modified(world.entity(caster).unwrap(), world.entity(target).unwrap());
+            assert_eq!(modified.multiplier, multiplier);
+            assert_eq!(matches!(modified.damage_type, DamageType::Fire), fire);
+        }
+    }
+
+    fn burning(world: &World, target: Entity) -> (u8, u8) {
+        let burning = world.get::<&Burning>(target).unwrap();
+        (burning.stacks, burning.turns)
+    }
+
+    #[test]
+    fn burning_stacks_up_to_the_cap() {
+        let mut world = World::new();
+        let target = world.spawn((Health(1),));
+        let mut events = Vec::new();
+        for _ in 0..4 {
+            let debuff = Debuff::Burning {
+                stacks: 2,
+                duration: 3,
+            };
+            apply_debuff(&mut world, target, debuff, &mut events);
+        }
+        assert_eq!(burning(&world, target), (MAX_BURNING_STACKS, 3));
+    }
+
+    #[test]
+    fn burning_refreshes_to_the_longer_duration() {
+        let mut world = World::new();
+        let target = world.spawn((Health(1),));
+        let mut events = Vec::new();
+        let mut apply = |world: &mut World, duration| {
+            let debuff = Debuff::Burning {
+                stacks: 1,
+                duration,
+            };
+            apply_debuff(world, target, debuff, &mut events);
+        };
+        apply(&mut world, 3);
+        apply(&mut world, 1);
+        assert_eq!(burning(&world, target), (2, 3));
+        apply(&mut world, 5);
+        assert_eq!(burning(&world, target), (3, 5));
+    }
+
+    #[test]
+    fn random_effects_follow_their_weights() {
+        let skill = Skill::builder("Coin Toss")
+            .target(PrimaryTarget::Caster)
+            .effect(Effect::Random(vec![
+                (
+                    1,
+                    Effect::Buff(Buff::Haste { duration: 1 }, EffectTarget::Caster),
+                ),
+                (
+                    3,
+                    Effect::Buff(Buff::Shell { duration: 1 }, EffectTarget::Caster),
+                ),
+            ]))
+            .build();
+        let mut world = World::new();
+        let caster = fighter(&mut world, 20);
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut hastes, mut shells) = (0, 0);
+        for _ in 0..1000 {
+            for event in skill.apply(&mut world, caster, &vec![caster], &mut rng) {
+                if let CombatEvent::Rolled { outcome, .. } = event {
+                    match outcome.as_str() {
+                        "Haste" => hastes += 1,
+                        "Shell" => shells += 1,
+                        other => panic!("Unexpected outcome {other}"),
+                    }
+                }
+            }
+        }
+        assert_eq!(hastes + shells, 1000);
+        // Weighted 1 to 3, so about a quarter should be hastes
+        assert!(
+            (200..300).contains(&hastes),
+            "{hastes} hastes, {shells} shells"
+        );
+    }
+
+    #[test]
+    fn defending_halves_damage() {
+        let skill = Skill::builder("Steady Blow")
+            .target(PrimaryTarget::Hostile)
+            .effect(Effect::damage().base(40).variance(0.).build())
+            .build();
+        let mut world = World::new();
+        let caster = fighter(&mut world, 20);
+        let target = fighter(&mut world, 20);
+        assert_eq!(hit(&mut world, &skill, caster, target), 40);
+        world.insert_one(target, Defending).unwrap();
+        assert_eq!(hit(&mut world, &skill, caster, target), 20);
+    }
+
+    #[test]
+    fn hits_stop_once_the_only_target_dies() {
+        for randomized in [true, false] {
+            let mut damage = Effect::damage().hits(6);
+            if randomized {
+                damage = damage.randomized();
+            }
+            let skill = Skill::builder("Flurry")
+                .target(PrimaryTarget::AllHostile)
+                .effect(damage.build())
+                .on_hit(Effect::Debuff(
+                    Debuff::Stunned { duration: 1 },
+                    EffectTarget::Target,
+                ))
+                .build();
+            let mut world = World::new();
+            let caster = fighter(&mut world, 20);
+            let target = fighter(&mut world, 20);
+            world.get::<&mut Health>(target).unwrap().0 = 1;
+            let events = skill.apply(
+                &mut world,
+                caster,
+                &vec![target],
+                &mut StdRng::seed_from_u64(1),
+            );
+            let count = |matches: fn(&CombatEvent) -> bool| {
+                events.iter().filter(|&event| matches(event)).count()
+            };
+            assert_eq!(count(|e| matches!(e, CombatEvent::DamageDealt { .. })), 1);
+            assert_eq!(count(|e| matches!(e, CombatEvent::Killed { .. })), 1);
+            assert_eq!(count(|e| matches!(e, CombatEvent::DebuffApplied { .. })), 1);
+            assert_eq!(world.get::<&Health>(target).unwrap().0, 0);
+        }
+    }
+
+    #[test]
+    fn status_changes_are_logged() {
+        let mut world = World::new();
+        let rat = fighter(&mut world, 20);
+        let drone = fighter(&mut world, 20);
+        let hero = fighter(&mut world, 20);
+        world.insert(rat, (Name("Sewer Rat"), Hostile)).unwrap();
+        world
+            .insert(
+                drone,
+                (
+                    Name("Necro-drone"),
+                    Hostile,
+                    Immunities::default().with::<Stunned>(),
+                ),
+            )
+            .unwrap();
+        world.insert_one(hero, Name("Vex")).unwrap();
+        let mut events = Vec::new();
+        let burning = Debuff::Burning {
+            stacks: 1,
+            duration: 1,
+        };
+        apply_debuff(&mut world, rat, burning, &mut events);
+        apply_debuff(&mut world, rat, burning, &mut events);
+        apply_debuff(
+            &mut world,
+            drone,
+            Debuff::Stunned { duration: 1 },
+            &mut events,
+        );
+        apply_buff(&mut world, hero, Buff::Haste { duration: 1 }, &mut events);
+        crate::app::tick_statuses(&mut world, rat, &[], &mut events);
+        crate::app::tick_statuses(&mut world, hero, &[], &mut events);
+        let lines = events
+            .iter()
+            .filter_map(|event| event.line(&world))
+            .collect::<Vec<_>>();
+        let log = lines
+            .iter()
+            .map(|line| {
+                let text = line.spans.iter().map(|span| span.content.as_ref());
+                text.collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            log,
+            [
+                "Sewer Rat is set ablaze (1)",
+                "Sewer Rat's Burning is refreshed (1)",
+                "Necro-drone is immune to Stunned!",
+                "Vex is hasted (1)",
+                "Sewer Rat's Burning wore off",
+                "Vex's Hasted wore off",
+            ]
+        );
+        // Green when it's good for the party, red when it's bad
+        let color = |line: usize| lines[line].spans.last().unwrap().style.fg;
+        assert_eq!(color(0), Some(Color::LightGreen));
+        assert_eq!(color(3), Some(Color::LightGreen));
+        assert_eq!(color(4), Some(Color::LightRed));
+        assert_eq!(color(5), Some(Color::LightRed));
+    }
 }
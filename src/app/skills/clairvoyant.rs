@@ -0,0 +1,137 @@
+use std::sync::LazyLock;
+
+use super::*;
+
+pub static SUPERNOVA: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Supernova")
+        .description("Burns everyone on the field, friend and foe alike, caster included.")
+        .target(PrimaryTarget::All)
+        .effect(
+            Effect::damage_type(DamageType::Fire)
+                .multiplier(1.2)
+                .build(),
+        )
+        .cooldown(3)
+        .build()
+});
+
+pub static DOOM: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Doom")
+        .description("Marks an enemy for heavy dark damage after three of its turns.")
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::Delayed {
+            turns: 3,
+            effects: vec![Effect::damage_type(DamageType::Dark).multiplier(3.).build()],
+        })
+        .cooldown(4)
+        .build()
+});
+
+pub static SUNBEAM: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Sunbeam")
+        .description("A lance of light that gathers a sun.")
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::damage_type(DamageType::Light).build())
+        .effect(Effect::Gain(Job::Clairvoyant { sun: 1, moon: 0 }))
+        .build()
+});
+
+pub static MOONSHADE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Moonshade")
+        .description("A creeping shadow that gathers a moon.")
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::damage_type(DamageType::Dark).build())
+        .effect(Effect::Gain(Job::Clairvoyant { sun: 0, moon: 1 }))
+        .build()
+});
+
+pub static ALIGNMENT: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Alignment")
+        .description(
+            "Calls on the stronger sky. Sun: light that heals the party. Moon: darkness that \
+             drains. Balanced: an eclipse of both.",
+        )
+        .target(PrimaryTarget::AllHostile)
+        .effect(
+            Effect::damage_type(DamageType::Light)
+                .multiplier(1.2)
+                .build(),
+        )
+        .effect(
+            Effect::damage_type(DamageType::Dark)
+                .multiplier(1.2)
+                .build(),
+        )
+        .cost(Job::Clairvoyant { sun: 2, moon: 2 })
+        .modifier(SkillModifier {
+            test: TestFn::Is(sun_is_higher),
+            effects: Some(vec![
+                Effect::damage_type(DamageType::Light)
+                    .multiplier(1.5)
+                    .build(),
+                Effect::damage_type(DamageType::Healing)
+                    .multiplier(0.2)
+                    .target(EffectTarget::Friendly)
+                    .build(),
+            ]),
+            cost: Some(Job::Clairvoyant { sun: 3, moon: 0 }.into()),
+            ..Default::default()
+        })
+        .modifier(SkillModifier {
+            test: TestFn::Is(moon_is_higher),
+            effects: Some(vec![
+                Effect::damage_type(DamageType::Dark)
+                    .multiplier(1.5)
+                    .lifesteal(0.5)
+                    .build(),
+            ]),
+            cost: Some(Job::Clairvoyant { sun: 0, moon: 3 }.into()),
+            ..Default::default()
+        })
+        .build()
+});
+
+pub static GAZE_INTO_TOMORROW: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Gaze Into Tomorrow")
+        .description(
+            "Spends a turn reading the stars, then strikes with blinding light. A big hit or a stun \
+             may break the vision.",
+        )
+        .target(PrimaryTarget::Hostile)
+        .effect(
+            Effect::damage_type(DamageType::Light)
+                .multiplier(3.5)
+                .build(),
+        )
+        .charged()
+        .cooldown(4)
+        .build()
+});
+
+pub static PREMONITION: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Premonition")
+        .description("Warns an ally of what's coming. They dodge the next attack aimed at them alone this round.")
+        .target(PrimaryTarget::Friendly)
+        .effect(Effect::Buff(
+            Buff::React {
+                name: "Premonition",
+                trigger: attacks_reactor,
+                response: Response::Dodge,
+            },
+            EffectTarget::Target,
+        ))
+        .cost(Job::Clairvoyant { sun: 0, moon: 1 })
+        .cooldown(2)
+        .build()
+});
+
+/// Level each skill is learned at
+pub static UNLOCKS: [(u8, &LazyLock<Skill>); 7] = [
+    (1, &SUNBEAM),
+    (2, &MOONSHADE),
+    (2, &PREMONITION),
+    (3, &ALIGNMENT),
+    (4, &DOOM),
+    (4, &GAZE_INTO_TOMORROW),
+    (5, &SUPERNOVA),
+];
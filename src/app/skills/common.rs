@@ -2,34 +2,61 @@ use std::sync::LazyLock;
 
 use super::*;
 
-pub static BASIC_ATTACK: LazyLock<Skill> = LazyLock::new(|| Skill {
-    name: "Basic Attack",
-    target: PrimaryTarget::Hostile,
-    effects: vec![Effect::damage().build()],
-    ..Default::default()
+pub static BASIC_ATTACK: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Basic Attack")
+        .description("A plain physical attack.")
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::damage().build())
+        .build()
 });
 
-pub static POTION: LazyLock<Skill> = LazyLock::new(|| Skill {
-    name: "Potion",
-    // target: PrimaryTarget::Friendly,
-    effects: vec![
-        Effect::damage_type(DamageType::Healing)
-            .multiplier(0.5)
-            .build(),
-    ],
-    ..Default::default()
+pub static POTION: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Potion")
+        .description("Restores half of max health.")
+        // .target(PrimaryTarget::Friendly)
+        .effect(
+            Effect::damage_type(DamageType::Healing)
+                .multiplier(0.5)
+                .build(),
+        )
+        .build()
 });
 
-pub static CLEANSE: LazyLock<Skill> = LazyLock::new(|| Skill {
-    name: "Cleanse",
-    target: PrimaryTarget::Friendly,
-    effects: vec![Effect::Buff(Buff::Cleansed, EffectTarget::Target)],
-    ..Default::default()
+pub static CLEANSE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Cleanse")
+        .description("Removes every debuff.")
+        .target(PrimaryTarget::Friendly)
+        .effect(Effect::Buff(Buff::Cleansed, EffectTarget::Target))
+        .build()
 });
 
-pub static REVIVE: LazyLock<Skill> = LazyLock::new(|| Skill {
-    name: "Revive",
-    target: PrimaryTarget::Friendly,
-    effects: vec![Effect::Buff(Buff::Revived, EffectTarget::Target)],
-    ..Default::default()
+pub static REVIVE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Revive")
+        .description("Brings a fallen ally back with a quarter of their health.")
+        .target(PrimaryTarget::DeadFriendly)
+        .effect(Effect::Buff(Buff::Revived, EffectTarget::Target))
+        .build()
+});
+
+pub static SCAN: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Scan")
+        .description("Reveals an enemy's health for the rest of the fight.")
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::Scan { resistances: false })
+        .build()
+});
+
+pub static EMP_GRENADE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("EMP Grenade")
+        .description("Hits every enemy for 25 electrical damage, no matter who throws it. May zap.")
+        .target(PrimaryTarget::AllHostile)
+        .effect(Effect::damage_type(DamageType::Electrical).base(25).build())
+        .effect(Effect::Chance(
+            0.5,
+            vec![Effect::Debuff(
+                Debuff::Zapped { duration: 2 },
+                EffectTarget::Target,
+            )],
+        ))
+        .build()
 });
@@ -0,0 +1,129 @@
+use std::sync::LazyLock;
+
+use super::*;
+
+pub static SCRAP_PLATING: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Scrap Plating")
+        .description("Bolts on junk armor that absorbs 20 damage.")
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Buff(
+            Buff::Barrier { amount: 20 },
+            EffectTarget::Target,
+        ))
+        .cooldown(3)
+        .build()
+});
+
+fn scrap_drone(world: &mut World) -> Entity {
+    world.spawn(NPCBundle {
+        name: Name("Scrap Drone"),
+        skills: Skills(vec![&SCRAP_PLATING]),
+        stats: Stats {
+            max_health: 50,
+            attack: 12,
+            speed: 120,
+            crit: 0.05,
+            defense: 15,
+            ..Default::default()
+        },
+        resistances: Resistances::default().with(DamageType::Electrical, 2.),
+        ..Default::default()
+    })
+}
+
+static SCRAP_DRONES: [EnemyTemplate; 1] = [EnemyTemplate {
+    spawn: scrap_drone,
+    levels: -1..=-1,
+    xp: 20,
+}];
+
+pub static CALL_REINFORCEMENTS: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Call Reinforcements")
+        .description("Summons a Scrap Drone to join the fight.")
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Summon(&SCRAP_DRONES))
+        .cooldown(4)
+        .build()
+});
+
+pub static PATCH_UP: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Patch Up")
+        .description("Welds an ally back together for 30% of their max health.")
+        .target(PrimaryTarget::Friendly)
+        .effect(
+            Effect::damage_type(DamageType::Healing)
+                .multiplier(0.3)
+                .build(),
+        )
+        .cooldown(2)
+        .build()
+});
+
+pub static BITE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Bite")
+        .description("Sinks its teeth in for 110% physical damage.")
+        .effect(Effect::damage().multiplier(1.1).build())
+        .build()
+});
+
+pub static INFECTIOUS_BITE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Infectious Bite")
+        .description("A filthy bite for 80% physical damage that makes the target contagious.")
+        .effect(Effect::damage().multiplier(0.8).build())
+        .effect(Effect::Debuff(
+            Debuff::Contagious { duration: 3 },
+            EffectTarget::Target,
+        ))
+        .cooldown(3)
+        .build()
+});
+
+pub static SMASH: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Smash")
+        .description("A heavy overhead blow for 150% physical damage.")
+        .effect(Effect::damage().multiplier(1.5).build())
+        .cooldown(1)
+        .build()
+});
+
+pub static SHED_SKIN: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Shed Skin")
+        .description("Sloughs off its rotting hide, and every debuff with it, behind 30 barrier.")
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Buff(Buff::Cleansed, EffectTarget::Target))
+        .effect(Effect::Buff(
+            Buff::Barrier { amount: 30 },
+            EffectTarget::Target,
+        ))
+        .build()
+});
+
+static SWARM: [EnemyTemplate; 2] = [
+    EnemyTemplate {
+        spawn: encounters::sewer_rat,
+        levels: -1..=0,
+        xp: 40,
+    },
+    EnemyTemplate {
+        spawn: encounters::sewer_rat,
+        levels: -1..=0,
+        xp: 40,
+    },
+];
+
+pub static CALL_THE_SWARM: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Call the Swarm")
+        .description("Summons two Sewer Rats to join the fight.")
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Summon(&SWARM))
+        .build()
+});
+
+pub static TAIL_SWEEP: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Tail Sweep")
+        .description("Sweeps the whole party for 70% physical damage.")
+        .target(PrimaryTarget::AllHostile)
+        .effect(Effect::damage().multiplier(0.7).build())
+        .cooldown(2)
+        .build()
+});
@@ -2,37 +2,209 @@ use std::sync::LazyLock;
 
 use super::*;
 
-pub static RELOAD: LazyLock<Skill> = LazyLock::new(|| Skill {
-    name: "Reload",
-    target: PrimaryTarget::Caster,
-    effects: vec![Effect::Gain(Job::Gunslinger { ammo: u8::MAX })],
-    ..Default::default()
-});
-
-pub static TACTICAL_RELOAD: LazyLock<Skill> = LazyLock::new(|| Skill {
-    name: "Tactical Reload",
-    target: PrimaryTarget::Caster,
-    effects: vec![
-        Effect::Buff(Buff::Shell { duration: 1 }, EffectTarget::Target),
-        Effect::Gain(Job::Gunslinger { ammo: u8::MAX }),
-    ],
-    cost: Job::Gunslinger { ammo: 1 },
-    ..Default::default()
-});
-
-pub static DOUBLE_TAP: LazyLock<Skill> = LazyLock::new(|| Skill {
-    name: "Double Tap",
-    target: PrimaryTarget::Hostile,
-    effects: vec![
-        Effect::damage()
-            .hits(2)
-            .modifier(DamageModifier {
-                test: TestFn(is_burning),
-                multiplier: Some(1.5),
-                ..Default::default()
-            })
-            .build(),
-    ],
-    cost: Job::Gunslinger { ammo: 2 },
-    ..Default::default()
+pub static RELOAD: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Reload")
+        .description("Refills the cylinder.")
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Gain(Job::Gunslinger { ammo: u8::MAX }))
+        .build()
 });
+
+pub static TACTICAL_RELOAD: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Tactical Reload")
+        .description("Reloads from cover, halving physical damage taken until the next turn.")
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Buff(
+            Buff::Shell { duration: 1 },
+            EffectTarget::Target,
+        ))
+        .effect(Effect::Gain(Job::Gunslinger { ammo: u8::MAX }))
+        .cost(Job::Gunslinger { ammo: 1 })
+        .cooldown(2)
+        .build()
+});
+
+pub static DOUBLE_TAP: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Double Tap")
+        .description("Two quick shots. Hits burning targets harder.")
+        .target(PrimaryTarget::Hostile)
+        .effect(
+            Effect::damage()
+                .hits(2)
+                .modifier(DamageModifier {
+                    test: TestFn::Is(is_burning),
+                    multiplier: Some(1.5),
+                    ..Default::default()
+                })
+                .build(),
+        )
+        .cost(Job::Gunslinger { ammo: 2 })
+        .build()
+});
+
+const EXPOSED_TESTS: [TestFn; 2] = [TestFn::Is(is_burning), TestFn::Is(target_is_frozen)];
+const EXPOSED: TestFn = any_of(&EXPOSED_TESTS);
+
+pub static DEADEYE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Deadeye")
+        .description(
+            "A careful shot that finishes off targets under 25% health and slows the wounded. \
+             Costs one less against burning or frozen targets. Ignores rows.",
+        )
+        .target(PrimaryTarget::Hostile)
+        .effect(
+            Effect::damage()
+                .ignores_rows()
+                .modifier(DamageModifier {
+                    test: TestFn::Is(is_below_quarter_health),
+                    multiplier: Some(4.),
+                    ..Default::default()
+                })
+                .build(),
+        )
+        .effect(Effect::Conditional(
+            TestFn::Is(is_below_half_health),
+            vec![Effect::Debuff(
+                Debuff::Slow { duration: 2 },
+                EffectTarget::Target,
+            )],
+        ))
+        .cost(Job::Gunslinger { ammo: 2 })
+        .modifier(SkillModifier {
+            test: EXPOSED,
+            cost: Some(Job::Gunslinger { ammo: 1 }.into()),
+            ..Default::default()
+        })
+        .build()
+});
+
+pub static FAN_THE_HAMMER: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Fan the Hammer")
+        .description("Empties the cylinder into random enemies, one hit per round.")
+        .target(PrimaryTarget::AllHostile)
+        .effect(
+            Effect::damage()
+                .multiplier(0.7)
+                .randomized()
+                .hits_per_spent()
+                .build(),
+        )
+        .cost(Cost::All(Job::Gunslinger { ammo: 1 }))
+        .build()
+});
+
+const HELPLESS_TESTS: [TestFn; 2] = [TestFn::Is(target_is_frozen), TestFn::Is(target_is_stunned)];
+const HELPLESS: TestFn = any_of(&HELPLESS_TESTS);
+
+pub static PISTOL_WHIP: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Pistol Whip")
+        .description("A free swing with the grip. Twice as hard against frozen or stunned targets.")
+        .target(PrimaryTarget::Hostile)
+        .effect(
+            Effect::damage()
+                .multiplier(0.8)
+                .modifier(DamageModifier {
+                    test: HELPLESS,
+                    multiplier: Some(2.),
+                    ..Default::default()
+                })
+                .build(),
+        )
+        .cooldown(2)
+        .build()
+});
+
+pub static DRAW_FIRE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Draw Fire")
+        .description("Taunts every enemy into attacking you for the next two rounds.")
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Buff(
+            Buff::Taunt { duration: 2 },
+            EffectTarget::Target,
+        ))
+        .cooldown(3)
+        .build()
+});
+
+pub static CALLED_SHOT: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Called Shot")
+        .description(
+            "A heavy aimed shot for 160% physical damage. A miss doesn't waste the rounds.",
+        )
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::damage().multiplier(1.6).build())
+        .on_miss(Effect::Gain(Job::Gunslinger { ammo: 2 }))
+        .cost(Job::Gunslinger { ammo: 2 })
+        .build()
+});
+
+pub static JACKPOT: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Jackpot")
+        .description("Spins the cylinder and lets fate pick the round. Might backfire.")
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::Random(vec![
+            (
+                3,
+                Effect::damage_type(DamageType::Fire)
+                    .multiplier(1.5)
+                    .build(),
+            ),
+            (
+                3,
+                Effect::damage_type(DamageType::Electrical)
+                    .multiplier(1.5)
+                    .build(),
+            ),
+            (
+                2,
+                Effect::Debuff(Debuff::Stunned { duration: 1 }, EffectTarget::Target),
+            ),
+            (1, Effect::damage().multiplier(4.).build()),
+            (1, Effect::damage().target(EffectTarget::Caster).build()),
+        ]))
+        .cost(Job::Gunslinger { ammo: 3 })
+        .cooldown(3)
+        .build()
+});
+
+/// Only ever used as Overwatch's follow-up
+pub static OVERWATCH_SHOT: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Overwatch Shot")
+        .description("A free shot at an exposed enemy.")
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::damage().multiplier(1.5).ignores_rows().build())
+        .build()
+});
+
+pub static OVERWATCH: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Overwatch")
+        .description(
+            "Covers the party until your next turn. Takes a free shot at the first enemy an ally \
+             stuns, freezes or zaps.",
+        )
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Buff(
+            Buff::Ready {
+                skill: &OVERWATCH_SHOT,
+                trigger: exposes_target,
+                duration: 2,
+            },
+            EffectTarget::Target,
+        ))
+        .cooldown(2)
+        .build()
+});
+
+/// Level each skill is learned at
+pub static UNLOCKS: [(u8, &LazyLock<Skill>); 10] = [
+    (1, &DOUBLE_TAP),
+    (2, &RELOAD),
+    (2, &PISTOL_WHIP),
+    (3, &TACTICAL_RELOAD),
+    (3, &DEADEYE),
+    (3, &CALLED_SHOT),
+    (4, &FAN_THE_HAMMER),
+    (4, &DRAW_FIRE),
+    (5, &OVERWATCH),
+    (5, &JACKPOT),
+];
@@ -2,20 +2,132 @@ use std::sync::LazyLock;
 
 use super::*;
 
-pub static STATIC_DISCHARGE: LazyLock<Skill> = LazyLock::new(|| Skill {
-    name: "Static Discharge",
-    target: PrimaryTarget::AllHostile,
-    effects: vec![
-        Effect::damage_type(DamageType::Electrical)
-            .hits(6)
-            .randomized()
-            .build(),
-    ],
-    on_crit: vec![
-        Effect::damage_type(DamageType::Electrical)
-            .randomized()
-            .target(EffectTarget::Hostile)
-            .build(),
-    ],
-    ..Default::default()
+pub static STATIC_DISCHARGE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Static Discharge")
+        .description("6 random electrical hits. Crits arc to another enemy. Heavy on the battery.")
+        .target(PrimaryTarget::AllHostile)
+        .effect(
+            Effect::damage_type(DamageType::Electrical)
+                .hits(6)
+                .randomized()
+                .scaling(0.05)
+                .build(),
+        )
+        .on_crit(
+            Effect::damage_type(DamageType::Electrical)
+                .randomized()
+                .target(EffectTarget::Hostile)
+                .build(),
+        )
+        .cost(Job::Nanovampire { battery: 40 })
+        .build()
 });
+
+pub static DRAIN: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Drain")
+        .description("Dark damage that heals for half the damage dealt.")
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::damage_type(DamageType::Dark).lifesteal(0.5).build())
+        .cost(Job::Nanovampire { battery: 20 })
+        .build()
+});
+
+pub static EXSANGUINATE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Exsanguinate")
+        .description("A dark strike for 130% damage. Refunds its battery if it kills.")
+        .target(PrimaryTarget::Hostile)
+        .effect(
+            Effect::damage_type(DamageType::Dark)
+                .multiplier(1.3)
+                .build(),
+        )
+        .on_kill(Effect::Gain(Job::Nanovampire { battery: 30 }))
+        .cost(Job::Nanovampire { battery: 30 })
+        .build()
+});
+
+const HOSTILE: TestFn = TestFn::Is(target_is_hostile_to_caster);
+
+pub static TRANSFUSION: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Transfusion")
+        .description("Drains an enemy's blood, or gives some of it to an ally.")
+        .target(PrimaryTarget::Any)
+        .effect(Effect::Conditional(
+            HOSTILE,
+            vec![Effect::damage_type(DamageType::Dark).lifesteal(0.5).build()],
+        ))
+        .effect(Effect::Conditional(
+            not(&HOSTILE),
+            vec![
+                Effect::damage_type(DamageType::Healing)
+                    .multiplier(0.25)
+                    .build(),
+            ],
+        ))
+        .cost(Job::Nanovampire { battery: 30 })
+        .build()
+});
+
+const LOW_HEALTH: TestFn = TestFn::Is(caster_is_low_health);
+const OVERCHARGED_TESTS: [TestFn; 2] = [TestFn::Is(caster_has_full_resource), not(&LOW_HEALTH)];
+const OVERCHARGED: TestFn = all_of(&OVERCHARGED_TESTS);
+
+pub static OVERCLOCK: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Overclock")
+        .description("A cheap jolt. Stronger on a full battery, unless badly hurt.")
+        .target(PrimaryTarget::Hostile)
+        .effect(
+            Effect::damage_type(DamageType::Electrical)
+                .multiplier(0.7)
+                .modifier(DamageModifier {
+                    test: OVERCHARGED,
+                    multiplier: Some(2.),
+                    ..Default::default()
+                })
+                .build(),
+        )
+        .cost(Job::Nanovampire { battery: 5 })
+        .build()
+});
+
+pub static BLOOD_SURGE: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Blood Surge")
+        .description("Burns your own blood for a heavy dark strike. Can't take you below 1 HP.")
+        .target(PrimaryTarget::Hostile)
+        .effect(
+            Effect::damage_type(DamageType::Dark)
+                .multiplier(2.2)
+                .build(),
+        )
+        .cost(Cost::Multiple(vec![
+            Cost::HealthPercent(0.15),
+            Job::Nanovampire { battery: 10 }.into(),
+        ]))
+        .cooldown(2)
+        .build()
+});
+
+pub static SIPHON: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Siphon")
+        .description("Feeds on an enemy, healing and recharging for part of the damage dealt.")
+        .target(PrimaryTarget::Hostile)
+        .effect(
+            Effect::damage_type(DamageType::Dark)
+                .multiplier(0.8)
+                .lifesteal(0.3)
+                .recharge(1.)
+                .build(),
+        )
+        .build()
+});
+
+/// Level each skill is learned at
+pub static UNLOCKS: [(u8, &LazyLock<Skill>); 7] = [
+    (1, &DRAIN),
+    (2, &SIPHON),
+    (2, &EXSANGUINATE),
+    (3, &STATIC_DISCHARGE),
+    (3, &OVERCLOCK),
+    (4, &TRANSFUSION),
+    (5, &BLOOD_SURGE),
+];
@@ -0,0 +1,85 @@
+use std::sync::LazyLock;
+
+use super::*;
+
+pub static SHORT_CIRCUIT: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Short Circuit")
+        .description("Fries an enemy's implants. Runs hot.")
+        .target(PrimaryTarget::Hostile)
+        .effect(
+            Effect::damage_type(DamageType::Electrical)
+                .multiplier(1.3)
+                .build(),
+        )
+        .effect(Effect::Gain(Job::Netrunner { ram: 0, heat: 25 }))
+        .cost(Job::Netrunner { ram: 2, heat: 0 })
+        .build()
+});
+
+pub static FIREWALL: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Firewall")
+        .description(
+            "Primes a firewall that negates the next electrical skill an enemy uses this round.",
+        )
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Buff(
+            Buff::React {
+                name: "Firewall",
+                trigger: is_electrical,
+                response: Response::Negate,
+            },
+            EffectTarget::Target,
+        ))
+        .effect(Effect::Gain(Job::Netrunner { ram: 0, heat: 20 }))
+        .cost(Job::Netrunner { ram: 3, heat: 0 })
+        .build()
+});
+
+pub static BOTNET: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Botnet")
+        .description("Zaps every enemy at once. Runs very hot.")
+        .target(PrimaryTarget::AllHostile)
+        .effect(
+            Effect::damage_type(DamageType::Electrical)
+                .multiplier(0.8)
+                .build(),
+        )
+        .effect(Effect::Debuff(
+            Debuff::Zapped { duration: 2 },
+            EffectTarget::Target,
+        ))
+        .effect(Effect::Gain(Job::Netrunner { ram: 0, heat: 45 }))
+        .cost(Job::Netrunner { ram: 4, heat: 0 })
+        .cooldown(2)
+        .build()
+});
+
+pub static VENT: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Vent")
+        .description("Dumps all heat and frees up 4GB of RAM.")
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Drain(Job::Netrunner {
+            ram: 0,
+            heat: u8::MAX,
+        }))
+        .effect(Effect::Gain(Job::Netrunner { ram: 4, heat: 0 }))
+        .build()
+});
+
+pub static DEEP_SCAN: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Deep Scan")
+        .description("Reads an enemy's health and resistances.")
+        .target(PrimaryTarget::Hostile)
+        .effect(Effect::Scan { resistances: true })
+        .cost(Job::Netrunner { ram: 1, heat: 0 })
+        .build()
+});
+
+/// Level each skill is learned at
+pub static UNLOCKS: [(u8, &LazyLock<Skill>); 5] = [
+    (1, &SHORT_CIRCUIT),
+    (2, &VENT),
+    (3, &DEEP_SCAN),
+    (4, &FIREWALL),
+    (5, &BOTNET),
+];
@@ -0,0 +1,101 @@
+use super::*;
+
+pub fn is_burning(_caster: EntityRef, target: EntityRef) -> bool {
+    target.satisfies::<&Burning>()
+}
+
+pub fn target_is_frozen(_caster: EntityRef, target: EntityRef) -> bool {
+    target.satisfies::<&Frozen>()
+}
+
+pub fn target_is_stunned(_caster: EntityRef, target: EntityRef) -> bool {
+    target.satisfies::<&Stunned>()
+}
+
+pub fn target_is_hostile_to_caster(caster: EntityRef, target: EntityRef) -> bool {
+    caster.satisfies::<&Hostile>() != target.satisfies::<&Hostile>()
+}
+
+fn health_fraction(entity: EntityRef) -> f32 {
+    match (entity.get::<&Health>(), entity.get::<&Stats>()) {
+        (Some(health), Some(stats)) => health.0 as f32 / stats.max_health as f32,
+        _ => 1.,
+    }
+}
+
+pub fn is_below_half_health(_caster: EntityRef, target: EntityRef) -> bool {
+    health_fraction(target) < 0.5
+}
+
+pub fn is_below_quarter_health(_caster: EntityRef, target: EntityRef) -> bool {
+    health_fraction(target) < 0.25
+}
+
+pub fn caster_is_low_health(caster: EntityRef, _target: EntityRef) -> bool {
+    health_fraction(caster) < 0.25
+}
+
+/// Only jobs with a known maximum can be full
+pub fn caster_has_full_resource(caster: EntityRef, _target: EntityRef) -> bool {
+    caster.get::<&Job>().is_some_and(|job| match *job {
+        Job::Gunslinger { ammo } => ammo >= MAX_AMMO,
+        Job::Nanovampire { battery } => battery >= MAX_BATTERY,
+        _ => false,
+    })
+}
+
+fn sun_and_moon(caster: EntityRef) -> Option<(u8, u8)> {
+    match *caster.get::<&Job>()? {
+        Job::Clairvoyant { sun, moon } => Some((sun, moon)),
+        _ => None,
+    }
+}
+
+pub fn sun_is_higher(caster: EntityRef, _target: EntityRef) -> bool {
+    sun_and_moon(caster).is_some_and(|(sun, moon)| sun > moon)
+}
+
+pub fn moon_is_higher(caster: EntityRef, _target: EntityRef) -> bool {
+    sun_and_moon(caster).is_some_and(|(sun, moon)| moon > sun)
+}
+
+/// Reaction trigger for a skill dealing electrical damage
+pub fn is_electrical(skill: &Skill, _targets: &[Entity], _reactor: Entity) -> bool {
+    skill.effects.iter().any(|effect| {
+        matches!(effect, Effect::Damage(damage, _)
+            if matches!(damage.damage_type, DamageType::Electrical))
+    })
+}
+
+/// Reaction trigger for an attack aimed at the reactor alone
+pub fn attacks_reactor(skill: &Skill, targets: &[Entity], reactor: Entity) -> bool {
+    targets == [reactor]
+        && skill.effects.iter().any(|effect| {
+            matches!(effect, Effect::Damage(damage, _)
+                if !matches!(damage.damage_type, DamageType::Healing))
+        })
+}
+
+/// Follow-up trigger for a target left stunned, frozen or zapped
+pub fn exposes_target(event: &CombatEvent) -> Option<Entity> {
+    match event {
+        CombatEvent::DebuffApplied {
+            target,
+            debuff: Debuff::Stunned { .. } | Debuff::Frozen { .. } | Debuff::Zapped { .. },
+            ..
+        } => Some(*target),
+        _ => None,
+    }
+}
+
+pub const fn all_of(tests: &'static [TestFn]) -> TestFn {
+    TestFn::AllOf(tests)
+}
+
+pub const fn any_of(tests: &'static [TestFn]) -> TestFn {
+    TestFn::AnyOf(tests)
+}
+
+pub const fn not(test: &'static TestFn) -> TestFn {
+    TestFn::Not(test)
+}
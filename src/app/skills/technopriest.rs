@@ -0,0 +1,95 @@
+use std::sync::LazyLock;
+
+use super::*;
+
+pub static AEGIS: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Aegis")
+        .description("Wraps an ally in a barrier that absorbs 30 damage.")
+        .target(PrimaryTarget::Friendly)
+        .effect(Effect::Buff(
+            Buff::Barrier { amount: 30 },
+            EffectTarget::Target,
+        ))
+        .cost(Job::Technopriest { prayers: 1 })
+        .build()
+});
+
+fn turret(world: &mut World) -> Entity {
+    world.spawn(NPCBundle {
+        name: Name("Turret"),
+        stats: Stats {
+            max_health: 100,
+            attack: 20,
+            speed: 120,
+            crit: 0.1,
+            defense: 20,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+}
+
+static TURRETS: [EnemyTemplate; 1] = [EnemyTemplate {
+    spawn: turret,
+    levels: 0..=0,
+    xp: 0,
+}];
+
+pub static DEPLOY_TURRET: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Deploy Turret")
+        .description("Deploys a turret that fights alongside the party until the battle ends.")
+        .target(PrimaryTarget::Caster)
+        .effect(Effect::Summon(&TURRETS))
+        .cost(Job::Technopriest { prayers: 2 })
+        .cooldown(5)
+        .build()
+});
+
+pub static MEND: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Mend")
+        .description("Patches up an ally for 30% of their max health.")
+        .target(PrimaryTarget::Friendly)
+        .effect(
+            Effect::damage_type(DamageType::Healing)
+                .multiplier(0.3)
+                .build(),
+        )
+        .cost(Job::Technopriest { prayers: 1 })
+        .build()
+});
+
+pub static BENEDICTION: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Benediction")
+        .description("Hastens an ally for two turns.")
+        .target(PrimaryTarget::Friendly)
+        .effect(Effect::Buff(
+            Buff::Haste { duration: 2 },
+            EffectTarget::Target,
+        ))
+        .cost(Job::Technopriest { prayers: 2 })
+        .build()
+});
+
+pub static LITANY: LazyLock<Skill> = LazyLock::new(|| {
+    Skill::builder("Litany")
+        .description("Spends every prayer to heal all living allies, 10% of max health per prayer.")
+        .target(PrimaryTarget::AllFriendly)
+        .effect(
+            Effect::damage_type(DamageType::Healing)
+                .multiplier(0.1)
+                .per_spent()
+                .target(EffectTarget::Friendly)
+                .build(),
+        )
+        .cost(Cost::All(Job::Technopriest { prayers: 1 }))
+        .build()
+});
+
+/// Level each skill is learned at
+pub static UNLOCKS: [(u8, &LazyLock<Skill>); 5] = [
+    (1, &MEND),
+    (2, &AEGIS),
+    (3, &BENEDICTION),
+    (4, &DEPLOY_TURRET),
+    (5, &LITANY),
+];
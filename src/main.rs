@@ -1,42 +1,83 @@
-use app::{App, Message};
-use color_eyre::eyre::Result;
+use app::{App, KeyMap};
+use color_eyre::eyre::{OptionExt, Result};
 use ratatui::{
     DefaultTerminal,
     crossterm::event::{self, Event},
 };
+use std::{path::PathBuf, time::Duration};
 use ui::ui;
 
 mod app;
 mod ui;
 
+/// How often the app gets to advance on its own when no keys come in
+const TICK_RATE: Duration = Duration::from_millis(50);
+
 fn main() -> Result<()> {
     color_eyre::install()?;
+    let seed = seed()?;
+    let keys = keys()?;
+    // Icons are drawn as plain text for terminals without the fonts for them
+    let ascii = std::env::args().any(|arg| arg == "--ascii");
     let terminal = ratatui::init();
-    let result = run(terminal);
+    let result = run(terminal, seed, keys, ascii);
     ratatui::restore();
     result
 }
 
-fn run(mut terminal: DefaultTerminal) -> Result<()> {
-    let mut app = App::new();
-    app.start_combat(app::Advantage::Neutral);
+/// `--seed <n>` replays the same rolls, given the same inputs
+fn seed() -> Result<Option<u64>> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            let seed = args.next().ok_or_eyre("--seed needs a number")?;
+            return Ok(Some(seed.parse()?));
+        }
+    }
+    Ok(None)
+}
+
+/// Keybindings from `$XDG_CONFIG_HOME/term-jrpg/keys.conf` (or `~/.config/...`), if there are any
+fn keys() -> Result<KeyMap> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    match config.map(|config| config.join("term-jrpg").join("keys.conf")) {
+        Some(path) if path.exists() => KeyMap::load(&path),
+        _ => Ok(KeyMap::default()),
+    }
+}
+
+fn run(mut terminal: DefaultTerminal, seed: Option<u64>, keys: KeyMap, ascii: bool) -> Result<()> {
+    let mut app = App::new(seed, keys, ascii);
+    let encounter = app.random_encounter();
+    app.start_combat(encounter);
+    // Idling on a menu draws nothing until a key, a resize or the fight clock changes something
+    let mut dirty = true;
     loop {
-        terminal.draw(|f| ui(f, &mut app))?;
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Release {
-                continue;
-            }
-            let Some(mut message) = app.handle_key(key) else {
-                continue;
-            };
-            while let Some(new_message) = app.update(message) {
-                if matches!(new_message, Message::Quit) {
+        dirty |= app.tick();
+        if dirty {
+            terminal.draw(|f| ui(f, &mut app))?;
+            dirty = false;
+        }
+        if !event::poll(TICK_RATE)? {
+            continue;
+        }
+        match event::read()? {
+            Event::Key(key) => {
+                if key.kind == event::KeyEventKind::Release {
+                    continue;
+                }
+                let Some(message) = app.handle_key(key) else {
+                    continue;
+                };
+                dirty |= app.update(message);
+                if app.exiting {
                     return Ok(());
                 }
-
-                terminal.draw(|f| ui(f, &mut app))?;
-                message = new_message;
             }
+            Event::Resize(..) => dirty = true,
+            _ => {}
         }
     }
 }
@@ -1,105 +1,326 @@
-use std::u32;
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
-use hecs::With;
+use hecs::{Entity, Satisfies, With};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Flex, Layout, Rect},
-    style::{Color, Style, Stylize},
+    style::{Color, Modifier, Style, Stylize},
+    symbols,
     text::{Line, Span, Text},
-    widgets::{
-        Block, Borders, Cell, Clear, Gauge, List, Paragraph, Row, Table, Wrap, block::Title,
-    },
+    widgets::{Block, Borders, Cell, Clear, Gauge, List, ListItem, Paragraph, Row, Table, Wrap},
 };
 
+use glyphs::Glyphs;
+
 use crate::app::{
-    App, Burning, CurrentScreen, GameState, Health, Hostile, Job, LOG, Level, Name, Party, Skills,
-    Stats,
+    Action, App, BUFFS, Barrier, Boss, Charging, Cost, CurrentScreen, DEBUFFS, Dead, Defending,
+    GAME_OVER_OPTIONS, GameState, Health, HealthShift, Hostile, Job, LOG, Level, Message, Name,
+    Party, PendingEffects, Resistances, Row as Formation, Scanned, Skill, Skills, Stats, Status,
+    Stunned, Xp, field_position, max_resources, xp_progress,
 };
 
+mod glyphs;
+
+/// Smallest terminal everything fits in
+const MIN_WIDTH: u16 = 90;
+const MIN_HEIGHT: u16 = 28;
+
 pub fn ui(frame: &mut Frame, app: &mut App) {
+    let area = frame.area();
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        let [message] = Layout::vertical([Constraint::Length(2)])
+            .flex(Flex::Center)
+            .areas(area);
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(format!(
+                    "Terminal too small (need {MIN_WIDTH}×{MIN_HEIGHT})"
+                ))
+                .bold(),
+                Line::from(format!("currently {}×{}", area.width, area.height)).dark_gray(),
+            ])
+            .centered(),
+            message,
+        );
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Min(1),
-            Constraint::Length(5),
+            // The party panel's rows plus its borders
+            Constraint::Length(party_rows(app) + 2),
             Constraint::Length(3),
         ])
         .split(frame.area());
 
-    draw_title(frame, chunks[0]);
+    draw_title(frame, chunks[0], app);
     draw_field(frame, chunks[1], app);
     draw_main(frame, chunks[2], app);
     draw_footer(frame, chunks[3], app);
+    draw_help(frame, app);
     draw_popup(frame, app);
 }
 
-fn draw_title(frame: &mut Frame, rect: Rect) {
+fn draw_title(frame: &mut Frame, rect: Rect, app: &App) {
     let title_block = Block::default()
         .title("Terminal JRPG")
         .borders(Borders::ALL)
         .style(Style::default());
-
-    let title = Paragraph::new(Text::styled(
+    let tagline = Text::styled(
         "The net is vast and full of terrors",
         Style::default().fg(Color::Green),
-    ))
-    .block(title_block);
+    );
+
+    // Nothing to say about where the party is, so just the tagline
+    let Some(encounter) = app.encounter else {
+        frame.render_widget(Paragraph::new(tagline).block(title_block), rect);
+        return;
+    };
+
+    let inner = title_block.inner(rect);
+    frame.render_widget(title_block, rect);
+    let [left, center, right] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(tagline.width() as u16),
+        Constraint::Fill(1),
+    ])
+    .spacing(2)
+    .areas(inner);
 
-    frame.render_widget(title, rect);
+    frame.render_widget(Line::from(encounter.name.bold()), left);
+    frame.render_widget(Paragraph::new(tagline), center);
+    if matches!(app.game_state, GameState::Combat) {
+        let elapsed = app.combat_started.elapsed().as_secs();
+        frame.render_widget(
+            Line::from(format!(
+                "Round {} · Turn {} · {}:{:02}",
+                app.round_number,
+                app.turn_number + 1,
+                elapsed / 60,
+                elapsed % 60
+            ))
+            .right_aligned(),
+            right,
+        );
+    }
 }
 
-fn draw_field(frame: &mut Frame, rect: Rect, app: &App) {
+fn draw_field(frame: &mut Frame, rect: Rect, app: &mut App) {
     match app.game_state {
         GameState::Combat => {
+            // Gives up the turn order first when the enemies don't fit, then their statuses
+            let room = rect.width.saturating_sub(48);
+            let show_order = enemies_width(app, false) + 20 <= room;
+            let compact = enemies_width(app, false) > room;
             let combat_chunks = Layout::horizontal(vec![
                 Constraint::Length(48),
                 Constraint::Fill(1),
-                Constraint::Length(20),
+                Constraint::Length(if show_order { 20 } else { 0 }),
             ])
             .split(rect);
             draw_log(frame, combat_chunks[0], app);
-            draw_enemies(frame, combat_chunks[1], app);
-            draw_order(frame, combat_chunks[2], app);
+            draw_enemies(frame, combat_chunks[1], app, compact);
+            if show_order {
+                draw_order(frame, combat_chunks[2], app);
+            }
+            draw_banner(frame, combat_chunks[1], app);
         }
+        GameState::Victory => {
+            let victory_chunks =
+                Layout::horizontal(vec![Constraint::Length(48), Constraint::Fill(1)]).split(rect);
+            draw_log(frame, victory_chunks[0], app);
+            draw_victory(frame, victory_chunks[1], app);
+        }
+        GameState::GameOver => draw_game_over(frame, rect, app),
         _ => unimplemented!(),
     }
 }
 
-fn draw_log(frame: &mut Frame, rect: Rect, _app: &App) {
+fn draw_log(frame: &mut Frame, rect: Rect, app: &mut App) {
     let log = LOG.lock().unwrap();
-    let log_lines = log.get_lines();
-    let num_lines = log_lines.len() as u16;
+    let paragraph = Paragraph::new(log.get_lines()).wrap(Wrap { trim: true });
+    // Wrapped rows, so long lines don't push the bottom out of view
+    let rows = paragraph.line_count(rect.width.saturating_sub(2)) as u16;
+    let bottom = rows.saturating_sub(rect.height.saturating_sub(2));
+    if app.log_scroll > 0 {
+        // Scrolled up, so new rows push the view up with them rather than yanking it down
+        app.log_scroll += rows.saturating_sub(app.log_rows);
+    }
+    app.log_scroll = app.log_scroll.min(bottom);
+    app.log_rows = rows;
+
+    let mut block = Block::default().title("Log").borders(Borders::ALL);
+    if app.log_scroll > 0 {
+        block = block.title_bottom(
+            if log.written() > app.log_seen {
+                Line::from(
+                    format!(
+                        "{} new messages {}",
+                        glyphs(app).more_below,
+                        app.keys.hint(&[Message::LogEnd])
+                    )
+                    .bold(),
+                )
+            } else {
+                Line::from(
+                    app.keys
+                        .hint(&[Message::LogUp, Message::LogDown, Message::LogEnd])
+                        .dark_gray(),
+                )
+            }
+            .right_aligned(),
+        );
+    }
     frame.render_widget(
-        Paragraph::new(log_lines)
-            // .wrap(Wrap { trim: true }) // TODO: This makes it hard to scroll to the bottom
-            .block(Block::default().title("Log").borders(Borders::ALL))
-            .scroll((num_lines.saturating_sub(rect.height.saturating_sub(2)), 0)),
+        paragraph.block(block).scroll((bottom - app.log_scroll, 0)),
         rect,
     );
 }
 
+/// An icon with the turns left for each of the entity's statuses, in registry order so the party
+/// and enemy panels agree
+fn status_icons(app: &App, entity: Entity) -> Vec<String> {
+    predicted_icons(app, entity, 0)
+}
+
+/// Status icons as they'll stand that many of the entity's turns from now, assuming each wears
+/// down by a turn every turn and nothing new gets applied
+fn predicted_icons(app: &App, entity: Entity, turns_ahead: u8) -> Vec<String> {
+    let glyphs = glyphs(app);
+    let mut icons = DEBUFFS
+        .iter()
+        .chain(BUFFS.iter())
+        .filter_map(|status| {
+            let turns = (status.turns_left)(&app.world, entity)?.checked_sub(turns_ahead)?;
+            if turns == 0 {
+                return None;
+            }
+            let icon = if app.ascii { status.ascii } else { status.icon };
+            Some(match (status.stacks)(&app.world, entity) {
+                1 => format!("{icon}{turns}"),
+                stacks => format!("{icon}{stacks}{}{turns}", glyphs.times),
+            })
+        })
+        .collect::<Vec<_>>();
+    if let Some(turns) = app
+        .world
+        .get::<&PendingEffects>(entity)
+        .ok()
+        .and_then(|pending| pending.countdown()?.checked_sub(turns_ahead))
+        .filter(|&turns| turns > 0)
+    {
+        icons.push(format!("{}{turns}", glyphs.pending));
+    }
+    icons
+}
+
+fn glyphs(app: &App) -> &'static Glyphs {
+    if app.ascii {
+        &glyphs::ASCII
+    } else {
+        &glyphs::UNICODE
+    }
+}
+
+/// As many icons as fit in the width, with a count of the ones left out
+fn fit_icons(icons: &[String], width: u16) -> Line<'static> {
+    let mut line = Line::default();
+    for (i, icon) in icons.iter().enumerate() {
+        let span = Span::from(if i == 0 {
+            icon.clone()
+        } else {
+            format!(" {icon}")
+        });
+        // Leaves room to say how many more there are, unless this is the last one
+        let left = icons.len() - i - 1;
+        let more = if left > 0 {
+            format!(" +{left}").len()
+        } else {
+            0
+        };
+        if line.width() + span.width() + more > width as usize {
+            let prefix = if i == 0 { "" } else { " " };
+            line.push_span(format!("{prefix}+{}", icons.len() - i).dark_gray());
+            break;
+        }
+        line.push_span(span);
+    }
+    line
+}
+
 struct EnemyInfo {
     name: &'static str,
     level: u8,
     health: u32,
     max_health: u32,
-    status: String,
+    barrier: u32,
+    status: Vec<String>,
     target: bool,
     turn: bool,
+    /// Current phase and phase count for bosses
+    phase: Option<(usize, usize)>,
+    back_row: bool,
+    scanned: bool,
+    /// Number floating over the enemy and the rows it has drifted up by
+    popup: Option<(Span<'static>, u16)>,
+    health_shift: Option<HealthShift>,
+    flash: Option<Color>,
 }
 
-fn draw_enemies(frame: &mut Frame, rect: Rect, app: &App) {
-    let enemy_info = app
+/// Room either side of the enemies for how many are off to that side
+const PAGE_MARKER_WIDTH: u16 = 4;
+
+/// Width of an enemy's card. Bosses take up two slots.
+fn enemy_width(boss: bool, compact: bool) -> u16 {
+    match (boss, compact) {
+        (false, false) => 20,
+        (true, false) => 40,
+        (false, true) => 14,
+        (true, true) => 28,
+    }
+}
+
+fn enemies_width(app: &App, compact: bool) -> u16 {
+    app.world
+        .query::<With<Satisfies<&Boss>, &Hostile>>()
+        .iter()
+        .map(|(_, boss)| enemy_width(boss, compact))
+        .sum()
+}
+
+/// Compact cards leave out the statuses, for when the full ones don't fit
+fn draw_enemies(frame: &mut Frame, rect: Rect, app: &App, compact: bool) {
+    let mut query = app
         .world
-        .query::<With<(&Name, &Level, &Health, &Stats), &Hostile>>()
+        .query::<With<(&Name, &Level, &Health, &Stats), &Hostile>>();
+    let mut enemies = query.iter().collect::<Vec<_>>();
+    enemies.sort_by_key(|&(entity, _)| field_position(&app.world, entity));
+    // Whoever the player is looking at: the enemy being aimed at, or else the one acting
+    let focus = enemies
         .iter()
+        .position(|&(entity, _)| {
+            matches!(app.current_screen, CurrentScreen::Target)
+                && app
+                    .selected_target
+                    .is_some_and(|selected| app.targets[selected] == entity)
+        })
+        .or_else(|| {
+            enemies
+                .iter()
+                .position(|&(entity, _)| app.turn == Some(entity))
+        })
+        .unwrap_or(0);
+    let enemy_info = enemies
+        .into_iter()
         .map(
             |(entity, (&Name(name), &Level(level), &Health(health), stats))| {
-                let mut status = String::new();
-                if let Ok(burning) = app.world.get::<&Burning>(entity) {
-                    status += &format!("🔥{}", burning.0);
-                }
+                let status = status_icons(app, entity);
 
                 let target = if matches!(app.current_screen, CurrentScreen::Target) {
                     match app.selected_target {
@@ -115,83 +336,426 @@ fn draw_enemies(frame: &mut Frame, rect: Rect, app: &App) {
                     level,
                     health,
                     max_health: stats.max_health,
+                    barrier: app
+                        .world
+                        .get::<&Barrier>(entity)
+                        .map_or(0, |barrier| barrier.0),
                     status,
                     target,
                     turn: app.turn.is_some_and(|t| t == entity),
+                    phase: app
+                        .world
+                        .get::<&Boss>(entity)
+                        .ok()
+                        .map(|boss| (boss.phase, boss.phases.len())),
+                    back_row: app
+                        .world
+                        .get::<&Formation>(entity)
+                        .is_ok_and(|row| *row == Formation::Back),
+                    scanned: app.world.satisfies::<&Scanned>(entity).unwrap_or(false),
+                    popup: app
+                        .popup(entity)
+                        .map(|popup| (popup.text.clone(), popup.rise())),
+                    health_shift: app.health_shift(entity),
+                    flash: app.flash(entity),
                 }
             },
         )
         .collect::<Vec<_>>();
 
-    let enemy_chunks = Layout::horizontal(vec![Constraint::Length(20); enemy_info.len()])
-        .flex(Flex::Center)
-        .split(rect);
+    // Too many even for compact cards gets split into pages, showing the one with the focus on it
+    let widths = enemy_info
+        .iter()
+        .map(|info| enemy_width(info.phase.is_some(), compact))
+        .collect::<Vec<_>>();
+    let mut page = 0..enemy_info.len();
+    if widths.iter().sum::<u16>() > rect.width {
+        let room = rect.width.saturating_sub(2 * PAGE_MARKER_WIDTH);
+        let mut start = 0;
+        while start < widths.len() {
+            let mut end = start + 1;
+            let mut width = widths[start];
+            while end < widths.len() && width + widths[end] <= room {
+                width += widths[end];
+                end += 1;
+            }
+            if (start..end).contains(&focus) {
+                page = start..end;
+                break;
+            }
+            start = end;
+        }
+    }
+    let [left, rect, right] = if page.len() < enemy_info.len() {
+        Layout::horizontal([
+            Constraint::Length(PAGE_MARKER_WIDTH),
+            Constraint::Fill(1),
+            Constraint::Length(PAGE_MARKER_WIDTH),
+        ])
+        .areas(rect)
+    } else {
+        [Rect::default(), rect, Rect::default()]
+    };
+    let hidden_left = page.start;
+    let hidden_right = enemy_info.len() - page.end;
+    if hidden_left > 0 {
+        frame.render_widget(
+            Text::from(format!("{}{hidden_left}", glyphs(app).more_left).dark_gray()).centered(),
+            Layout::vertical([Constraint::Length(1)])
+                .flex(Flex::Center)
+                .split(left)[0],
+        );
+    }
+    if hidden_right > 0 {
+        frame.render_widget(
+            Text::from(format!("{hidden_right}{}", glyphs(app).more_right).dark_gray()).centered(),
+            Layout::vertical([Constraint::Length(1)])
+                .flex(Flex::Center)
+                .split(right)[0],
+        );
+    }
 
-    enemy_info.iter().enumerate().for_each(|(i, info)| {
-        let centered = Layout::vertical(vec![Constraint::Length(1), Constraint::Length(4)])
+    let enemy_info = &enemy_info[page.clone()];
+    let enemy_chunks =
+        Layout::horizontal(widths[page].iter().map(|&width| Constraint::Length(width)))
             .flex(Flex::Center)
-            .split(enemy_chunks[i]);
+            .split(rect);
+
+    enemy_info.iter().enumerate().for_each(|(i, info)| {
+        // The front row stands a step closer to the party, below the back row
+        let gap = Constraint::Length(1);
+        let card = vec![
+            Constraint::Length(1),
+            Constraint::Length(if compact { 3 } else { 4 }),
+        ];
+        let centered = Layout::vertical(if info.back_row {
+            [card, vec![gap]].concat()
+        } else {
+            [vec![gap], card].concat()
+        })
+        .flex(Flex::Center)
+        .split(enemy_chunks[i]);
+        let centered = if info.back_row {
+            &centered[..2]
+        } else {
+            &centered[1..]
+        };
 
         if info.target {
-            frame.render_widget(Text::raw("⮟").centered(), centered[0]);
+            frame.render_widget(Text::raw(glyphs(app).target_enemy).centered(), centered[0]);
+        }
+        if let Some((text, rise)) = &info.popup {
+            let area = Rect {
+                y: centered[0].y.saturating_sub(*rise),
+                ..centered[0]
+            };
+            frame.render_widget(Line::from(text.clone()).centered(), area);
         }
 
-        frame.render_widget(
-            Block::default()
-                .title(
-                    Line::from(format!("{} Lv.{}", info.name, info.level)).style(if info.turn {
-                        Style::new().bold()
+        let mut block = Block::default()
+            .title(
+                Line::from(format!("{} Lv.{}", info.name, info.level)).style(if info.turn {
+                    Style::new().bold()
+                } else {
+                    Style::default()
+                }),
+            )
+            .borders(Borders::ALL);
+        if let Some(color) = info.flash {
+            block = block.border_style(Style::new().fg(color).bold());
+        }
+        if let Some((phase, phases)) = info.phase {
+            let pips = (0..phases)
+                .map(|i| {
+                    if i <= phase {
+                        glyphs(app).phase_done
                     } else {
-                        Style::default()
-                    }),
-                )
-                .borders(Borders::ALL),
-            centered[1],
-        );
+                        glyphs(app).phase_left
+                    }
+                })
+                .collect::<String>();
+            block = block.title(Line::from(pips.light_red()).right_aligned());
+        }
+        frame.render_widget(block, centered[1]);
         let info_chunks = Layout::vertical(vec![Constraint::Length(1), Constraint::Fill(1)])
             .margin(1)
             .split(centered[1]);
         let mut chunk = 0;
-        frame.render_widget(
-            Gauge::default()
-                .ratio(info.health as f64 / info.max_health as f64)
-                .label(format!("{}/{}", info.health, info.max_health))
-                .gauge_style(Color::Red),
+        let mut gauge = health_gauge(info.health, info.max_health, info.barrier);
+        if !info.scanned {
+            gauge = gauge.label("???");
+        }
+        render_health(
+            frame,
             info_chunks[chunk],
+            gauge,
+            info.health_shift,
+            info.max_health,
         );
 
+        if compact {
+            return;
+        }
         chunk += 1;
         frame.render_widget(
-            Paragraph::new(Text::raw(info.status.as_str())),
+            Paragraph::new(fit_icons(&info.status, info_chunks[chunk].width)),
             info_chunks[chunk],
         );
     });
 }
 
+fn draw_banner(frame: &mut Frame, rect: Rect, app: &App) {
+    let Some(banner) = &app.banner else {
+        return;
+    };
+    let [area] = Layout::vertical([Constraint::Length(3)])
+        .flex(Flex::Center)
+        .areas(rect);
+    let [area] = Layout::horizontal([Constraint::Length(banner.text.width() as u16 + 6)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(Line::from(banner.text.clone()).centered())
+            .block(Block::default().borders(Borders::ALL)),
+        area,
+    );
+}
+
+fn draw_game_over(frame: &mut Frame, rect: Rect, app: &mut App) {
+    let [title, options] = Layout::vertical([Constraint::Length(2), Constraint::Length(2)])
+        .flex(Flex::Center)
+        .areas(rect);
+    frame.render_widget(
+        Paragraph::new(Line::from("GAME OVER".bold().light_red()).centered()),
+        title,
+    );
+    let [options] = Layout::horizontal([Constraint::Length(20)])
+        .flex(Flex::Center)
+        .areas(options);
+    frame.render_stateful_widget(
+        List::new(GAME_OVER_OPTIONS).highlight_style(Style::new().reversed()),
+        options,
+        &mut app.game_over_list_state,
+    );
+}
+
+fn draw_victory(frame: &mut Frame, rect: Rect, app: &App) {
+    let summary = &app.summary;
+    let mut lines = vec![
+        Line::from("Victory!".bold().light_green()).centered(),
+        Line::default(),
+    ];
+
+    let mut defeated = Vec::<(&str, usize)>::new();
+    for &name in &summary.defeated {
+        match defeated.iter_mut().find(|(other, _)| *other == name) {
+            Some((_, count)) => *count += 1,
+            None => defeated.push((name, 1)),
+        }
+    }
+    lines.push(Line::from("Defeated".bold()));
+    lines.extend(
+        defeated
+            .iter()
+            .map(|(name, count)| Line::from(format!("  {name} ×{count}"))),
+    );
+
+    lines.push(Line::from("Experience".bold()));
+    lines.extend(summary.xp.iter().map(|&(name, xp, before)| {
+        let mut line = Line::from(vec![
+            format!("  {name} ").green(),
+            format!("+{xp} XP  ").into(),
+            xp_bar(app, before),
+            " → ".dark_gray(),
+            xp_bar(app, before + xp),
+        ]);
+        if let Some((_, level)) = summary.level_ups.iter().find(|(other, _)| *other == name) {
+            line.push_span(format!("  Level up! Lv.{level}").bold().light_yellow());
+        }
+        line
+    }));
+
+    lines.push(Line::from("Loot".bold()));
+    if summary.loot.is_empty() {
+        lines.push(Line::from("  Nothing".dark_gray()));
+    }
+    lines.extend(
+        summary
+            .loot
+            .iter()
+            .map(|item| Line::from(format!("  {item}"))),
+    );
+
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().title("Results").borders(Borders::ALL)),
+        rect,
+    );
+}
+
+/// Health fraction under which a gauge turns red and a party member's name warns of it
+const DANGER_HEALTH: f64 = 0.2;
+/// Health fraction under which a gauge turns yellow
+const LOW_HEALTH: f64 = 0.5;
+
+/// Zero max health reads as empty rather than a NaN ratio
+fn health_ratio(health: u32, max_health: u32) -> f64 {
+    if max_health == 0 {
+        0.
+    } else {
+        (health as f64 / max_health as f64).clamp(0., 1.)
+    }
+}
+
+/// Health gauge that goes from green to yellow to red as it empties
+fn health_gauge(health: u32, max_health: u32, barrier: u32) -> Gauge<'static> {
+    let ratio = health_ratio(health, max_health);
+    Gauge::default()
+        .ratio(ratio)
+        .label(health_label(health, max_health, barrier))
+        .gauge_style(if ratio < DANGER_HEALTH {
+            Color::Red
+        } else if ratio < LOW_HEALTH {
+            Color::Yellow
+        } else {
+            Color::Green
+        })
+}
+
+/// Draws the gauge with a change in health playing out: what was lost trails behind in a dimmer shade
+/// until it catches up, and what was healed fills in green
+fn render_health(
+    frame: &mut Frame,
+    area: Rect,
+    gauge: Gauge<'static>,
+    shift: Option<HealthShift>,
+    max_health: u32,
+) {
+    let Some(shift) = shift else {
+        frame.render_widget(gauge, area);
+        return;
+    };
+    let shown = shift.shown();
+    if shift.to > shift.from {
+        frame.render_widget(
+            gauge
+                .ratio(health_ratio(shown, max_health))
+                .gauge_style(Color::LightGreen),
+            area,
+        );
+        return;
+    }
+    frame.render_widget(gauge, area);
+    // Rounded the same way the gauge rounds its own fill
+    let cells = |health| (health_ratio(health, max_health) * area.width as f64).round() as u16;
+    let buffer = frame.buffer_mut();
+    for x in area.left() + cells(shift.to)..area.left() + cells(shown) {
+        let cell = &mut buffer[(x, area.top())];
+        // The label stays readable on top
+        if cell.symbol() == " " {
+            cell.set_symbol(symbols::block::FULL)
+                .modifier
+                .insert(Modifier::DIM);
+        }
+    }
+}
+
+/// Enemies keep their exact health to themselves until they're scanned
+fn health_hidden(app: &App, entity: Entity) -> bool {
+    app.world.satisfies::<&Hostile>(entity).unwrap_or(false)
+        && !app.world.satisfies::<&Scanned>(entity).unwrap_or(false)
+}
+
+/// Barrier is shown in blue after the health numbers
+fn health_label(health: u32, max_health: u32, barrier: u32) -> Span<'static> {
+    if barrier > 0 {
+        format!("{health}/{max_health} +{barrier}").light_blue()
+    } else {
+        format!("{health}/{max_health}").into()
+    }
+}
+
 fn draw_order(frame: &mut Frame, rect: Rect, app: &App) {
     let Some(next_up) = app.next_up.clone() else {
         return;
     };
+    // Play the order forward to see where the next round starts
+    let living = next_up.0.iter().map(|i| i.entity).collect::<HashSet<_>>();
+    let mut acted = app.acted.clone();
+    let mut round = app.round_number;
+    let height = rect.height.saturating_sub(2) as usize;
+    let width = rect.width.saturating_sub(2);
+    let now = next_up.0.peek().map_or(0., |i| i.initiative);
+    // Turns each entity has coming before the one being drawn, for predicting their statuses
+    let mut turns_ahead = HashMap::new();
+    let mut lines = Vec::new();
+    let mut dividers = Vec::new();
+    for (n, i) in next_up.enumerate() {
+        if living.is_subset(&acted) {
+            round += 1;
+            acted.clear();
+            dividers.push(lines.len());
+            lines.push(
+                Line::from(format!("── Round {round} ──"))
+                    .dark_gray()
+                    .dim()
+                    .centered(),
+            );
+        }
+        if lines.len() > height {
+            break;
+        }
+        acted.insert(i.entity);
+        let ahead = turns_ahead.entry(i.entity).or_insert(0u8);
+
+        let name = app.world.get::<&Name>(i.entity).unwrap().0;
+        let mut spans = Vec::new();
+        if app.world.satisfies::<&Charging>(i.entity).unwrap_or(false) {
+            spans.push(format!("{} ", glyphs(app).charging).light_yellow());
+        } else if *ahead == 0 && app.world.satisfies::<&Stunned>(i.entity).unwrap_or(false) {
+            let icon = if app.ascii {
+                Stunned::ASCII
+            } else {
+                Stunned::ICON
+            };
+            spans.push(format!("{icon} ").light_yellow());
+        }
+        spans.push(name.into());
+        // How long until it comes up, to see what speed changes do to the order
+        if n > 0 {
+            let ticks = ((i.initiative - now) * 1000.).round() as u32;
+            spans.push(format!(" +{ticks}").dark_gray());
+        }
+        let mut line = Line::from(spans);
+        let room = width.saturating_sub(line.width() as u16 + 1);
+        let icons = fit_icons(&predicted_icons(app, i.entity, *ahead), room);
+        if !icons.spans.is_empty() {
+            line.push_span(" ");
+            line.spans.extend(icons.spans);
+        }
+        *ahead = ahead.saturating_add(1);
+
+        line = if i.hostile {
+            line.right_aligned().style(Color::LightRed)
+        } else {
+            line.left_aligned().style(Color::Green)
+        };
+        if n == 0 {
+            line = line.bold()
+        }
+        lines.push(line);
+    }
+    // A cut at a round boundary reads better than one partway through, as long as it doesn't
+    // leave too much of the panel empty
+    let cut = dividers
+        .into_iter()
+        .rfind(|&divider| divider <= height && divider > height / 2)
+        .filter(|_| lines.len() > height)
+        .unwrap_or(height);
+    lines.truncate(cut);
     frame.render_widget(
-        Paragraph::new(Text::from(
-            next_up
-                .take(rect.height as usize - 2)
-                .enumerate()
-                .map(|(n, i)| {
-                    let name = app.world.get::<&Name>(i.entity).unwrap().0;
-                    let mut line = if i.hostile {
-                        Line::raw(name).right_aligned().style(Color::LightRed)
-                    } else {
-                        Line::raw(name).left_aligned().style(Color::Green)
-                    };
-                    if n == 0 {
-                        line = line.bold()
-                    }
-                    line
-                })
-                .collect::<Vec<_>>(),
-        ))
-        .block(Block::default().title("Next up").borders(Borders::ALL)),
+        Paragraph::new(Text::from(lines))
+            .block(Block::default().title("Next up").borders(Borders::ALL)),
         rect,
     );
 }
@@ -208,18 +772,21 @@ fn draw_main(frame: &mut Frame, rect: Rect, app: &mut App) {
     match app.current_screen {
         CurrentScreen::Skill => draw_skills(frame, rect, app),
         CurrentScreen::Item => draw_items(frame, rect, app),
+        CurrentScreen::Target => draw_target_preview(frame, rect, app),
         _ => (),
     }
 }
 
 fn draw_actions(frame: &mut Frame, rect: Rect, app: &mut App) {
     let action_block = Block::default()
-        .title("Actions ↓↑")
+        .title(format!("Actions {}", glyphs(app).up_down))
         .borders(Borders::ALL)
         .style(Style::default());
 
-    if matches!(app.current_screen, CurrentScreen::Enemy) {
-        // Draw empty box during enemy's turn
+    if matches!(app.current_screen, CurrentScreen::Enemy)
+        || matches!(app.game_state, GameState::Victory | GameState::GameOver)
+    {
+        // Draw empty box during enemy's turn and after the fight
         frame.render_widget(action_block, rect);
     } else {
         let items = app
@@ -236,22 +803,82 @@ fn draw_actions(frame: &mut Frame, rect: Rect, app: &mut App) {
     }
 }
 
+/// Rows the party panel keeps even for a small party, so the actions list still fits
+const MIN_PARTY_ROWS: u16 = 5;
+/// Rows past which the party panel scrolls instead of growing
+const MAX_PARTY_ROWS: u16 = 6;
+
+fn party_rows(app: &App) -> u16 {
+    let members = app.world.query::<With<(), &Party>>().iter().count() as u16;
+    members.clamp(MIN_PARTY_ROWS, MAX_PARTY_ROWS)
+}
+
 fn draw_party(frame: &mut Frame, rect: Rect, app: &App) {
-    let party_block = Block::default()
+    let mut query = app
+        .world
+        .query::<With<(&Name, &Health, &Stats, &Job), &Party>>();
+    let mut members = query.iter().collect::<Vec<_>>();
+    members.sort_by_key(|&(entity, _)| field_position(&app.world, entity));
+
+    let rows = rect.height.saturating_sub(2) as usize;
+    // Scrolled just far enough to keep whoever is acting or targeted in view
+    let focus = members
+        .iter()
+        .rposition(|&(entity, _)| {
+            app.turn == Some(entity)
+                || matches!(app.current_screen, CurrentScreen::Target)
+                    && app
+                        .selected_target
+                        .is_some_and(|selected| app.targets[selected] == entity)
+        })
+        .unwrap_or(0);
+    let offset = (focus + 1)
+        .saturating_sub(rows)
+        .min(members.len().saturating_sub(rows));
+
+    let mut party_block = Block::default()
         .title("Party")
         .borders(Borders::ALL)
         .style(Style::default());
+    let hidden_above = offset;
+    let hidden_below = members.len().saturating_sub(offset + rows);
+    if hidden_above + hidden_below > 0 {
+        party_block = party_block.title_bottom(
+            Line::from(
+                format!(
+                    "{}{hidden_above} {}{hidden_below}",
+                    glyphs(app).more_above,
+                    glyphs(app).more_below
+                )
+                .dark_gray(),
+            )
+            .right_aligned(),
+        );
+    }
 
     frame.render_widget(party_block, rect);
 
-    let party_chunks = Layout::vertical([Constraint::Length(1); 3])
+    // Wide enough for the widest resources, in whichever glyphs are in use
+    let resource_width = members
+        .iter()
+        .map(|&(entity, (_, _, _, job))| resource_line(app, entity, job).width())
+        .max()
+        .unwrap_or(0) as u16;
+
+    // XP only gets a column when statuses would still have room beside it
+    let xp_width = RESOURCE_BAR_WIDTH as u16;
+    let fixed = 1 + 18 + 16 + 5 + resource_width + xp_width + 2 * 6;
+    let show_xp = rect.width.saturating_sub(4) >= fixed + 16;
+
+    let party_chunks = Layout::vertical(vec![Constraint::Length(1); rows])
         .vertical_margin(1)
         .horizontal_margin(2)
         .split(rect);
 
-    app.world
-        .query::<With<(&Name, &Health, &Stats, &Job), &Party>>()
-        .iter()
+    members
+        .into_iter()
+        .skip(offset)
+        .take(rows)
         .enumerate()
         .for_each(
             |(i, (entity, (&Name(name), &Health(health), stats, job)))| {
@@ -259,8 +886,11 @@ fn draw_party(frame: &mut Frame, rect: Rect, app: &App) {
                     .direction(Direction::Horizontal)
                     .constraints([
                         Constraint::Length(1),
+                        Constraint::Length(18),
                         Constraint::Length(16),
-                        Constraint::Length(16),
+                        Constraint::Length(5),
+                        Constraint::Length(resource_width),
+                        Constraint::Length(if show_xp { xp_width } else { 0 }),
                         Constraint::Fill(1),
                     ])
                     .spacing(2)
@@ -268,109 +898,384 @@ fn draw_party(frame: &mut Frame, rect: Rect, app: &App) {
 
                 let mut chunk = 0;
                 if matches!(app.current_screen, CurrentScreen::Target)
-                    && let Some(selected) = app.selected_target
-                    && app.targets[selected] == entity
+                    && match app.selected_target {
+                        None => app.targets.contains(&entity),
+                        Some(selected) => app.targets[selected] == entity,
+                    }
                 {
-                    frame.render_widget(Paragraph::new("⮞"), character_chunks[chunk]);
+                    frame.render_widget(
+                        Paragraph::new(glyphs(app).target_ally),
+                        character_chunks[chunk],
+                    );
                 }
 
                 chunk += 1;
-                let mut name =
-                    Paragraph::new(Text::styled(name, Color::Gray)).block(Block::default());
+                let dead = app.world.satisfies::<&Dead>(entity).unwrap_or(false);
+                // Only revives can target the dead, so they light back up while one is aimed
+                let revivable = dead
+                    && matches!(app.current_screen, CurrentScreen::Target)
+                    && app.targets.contains(&entity);
+                // The back row is indented, a step further from the enemies
+                let indent = match app.world.get::<&Formation>(entity).as_deref() {
+                    Ok(Formation::Back) => "  ",
+                    _ => "",
+                };
+                let danger = health > 0 && health_ratio(health, stats.max_health) < DANGER_HEALTH;
+                let mut name_line = if dead && !revivable {
+                    Line::styled(format!("{indent}{name}"), Color::DarkGray)
+                } else if danger {
+                    Line::styled(format!("{indent}{name}"), Color::LightRed).bold()
+                } else {
+                    Line::styled(format!("{indent}{name}"), Color::Gray)
+                };
+                if dead {
+                    name_line.push_span(format!(" {}", glyphs(app).dead));
+                }
+                if app.world.satisfies::<&Defending>(entity).unwrap_or(false) {
+                    name_line.push_span(format!(" {}", glyphs(app).defending));
+                }
+                let mut name = Paragraph::new(name_line).block(Block::default());
                 if let Some(ent) = app.turn
                     && ent == entity
+                    && !dead
                 {
                     name = name.bold();
                 }
+                // Party members have no card, so their name lights up instead
+                if let Some(color) = app.flash(entity) {
+                    name = name.black().bg(color);
+                }
                 frame.render_widget(name, character_chunks[chunk]);
 
                 chunk += 1;
-                frame.render_widget(
-                    Gauge::default()
-                        .ratio(health as f64 / stats.max_health as f64)
-                        .label(format!("{}/{}", health, stats.max_health))
-                        .gauge_style(Color::Red),
+                render_health(
+                    frame,
                     character_chunks[chunk],
+                    if dead {
+                        Gauge::default()
+                            .ratio(0.)
+                            .label("DOWN")
+                            .gauge_style(Color::DarkGray)
+                    } else {
+                        health_gauge(
+                            health,
+                            stats.max_health,
+                            app.world
+                                .get::<&Barrier>(entity)
+                                .map_or(0, |barrier| barrier.0),
+                        )
+                    },
+                    app.health_shift(entity),
+                    stats.max_health,
                 );
 
+                chunk += 1;
+                if let Some(popup) = app.popup(entity) {
+                    frame.render_widget(Line::from(popup.text.clone()), character_chunks[chunk]);
+                }
+
+                chunk += 1;
+                if !dead {
+                    frame.render_widget(
+                        Paragraph::new(resource_line(app, entity, job)),
+                        character_chunks[chunk],
+                    );
+                }
+
+                chunk += 1;
+                if show_xp && let Ok(xp) = app.world.get::<&Xp>(entity) {
+                    frame.render_widget(xp_bar(app, xp.0), character_chunks[chunk]);
+                }
+
                 chunk += 1;
                 frame.render_widget(
-                    Paragraph::new(match job {
-                        Job::Gunslinger { ammo } => {
-                            Line::from(vec![format!("⁍ {}", ammo).dark_gray()])
-                        }
-                        Job::Netrunner { ram, heat } => Line::from(vec![
-                            format!("{}GB", ram).blue(),
-                            format!("  {}ºC", heat).light_red(),
-                        ]),
-                        Job::Technopriest { prayers } => {
-                            Line::from(format!("✠ {}", prayers)).light_green()
-                        }
-                        Job::Clairvoyant { sun, moon } => Line::from(vec![
-                            format!("☀ {}", sun).yellow(),
-                            format!("  ☽︎ {}", moon).magenta(),
-                        ]),
-                        Job::Nanovampire { battery } => {
-                            // TODO: Find less risky character? This one probably won't always fill two cells.
-                            Line::from(vec![format!("⚡{}%", battery).light_blue()])
-                        }
-                        Job::None => Line::raw(""),
-                    }),
+                    fit_icons(&status_icons(app, entity), character_chunks[chunk].width),
                     character_chunks[chunk],
-                )
+                );
             },
         );
 }
 
-fn draw_skills(frame: &mut Frame, rect: Rect, app: &mut App) {
-    let rect = Layout::horizontal(vec![Constraint::Length(20)])
+/// Cells in the bars for heat, battery and XP
+const RESOURCE_BAR_WIDTH: u32 = 8;
+
+fn bar(glyphs: &Glyphs, value: u32, max: u32) -> String {
+    let filled = (value.min(max) * RESOURCE_BAR_WIDTH).div_ceil(max.max(1));
+    glyphs.bar_full.repeat(filled as usize)
+        + &glyphs
+            .bar_empty
+            .repeat((RESOURCE_BAR_WIDTH - filled) as usize)
+}
+
+/// How far along to the next level that much total XP is
+fn xp_bar(app: &App, xp: u32) -> Span<'static> {
+    match xp_progress(xp) {
+        Some((earned, needed)) => bar(glyphs(app), earned, needed).light_cyan(),
+        None => format!("{:^width$}", "MAX", width = RESOURCE_BAR_WIDTH as usize).light_yellow(),
+    }
+}
+
+/// A party member's job resources, as shown in the party panel. Counted resources are pips up to
+/// the most the job can hold, and percent-like ones are bars.
+fn resource_line(app: &App, entity: Entity, job: &Job) -> Line<'static> {
+    let glyphs = glyphs(app);
+    let level = app.world.get::<&Level>(entity).map_or(1, |level| level.0);
+    let pips = |count: u8, max: u8| {
+        glyphs.pip_full.repeat(count.min(max) as usize)
+            + &glyphs.pip_empty.repeat(max.saturating_sub(count) as usize)
+    };
+    let bar = |value: u8, max: u8| bar(glyphs, value as u32, max as u32);
+    match (*job, max_resources(job, level)) {
+        (Job::Gunslinger { ammo }, Job::Gunslinger { ammo: max_ammo }) => {
+            Line::from(format!("{}{}", glyphs.ammo, pips(ammo, max_ammo)).dark_gray())
+        }
+        (Job::Netrunner { ram, heat }, Job::Netrunner { heat: overheat, .. }) => {
+            let heat_bar = bar(heat, overheat);
+            Line::from(vec![
+                format!("{ram}{} ", glyphs.ram).blue(),
+                if heat as u32 >= overheat as u32 * 3 / 4 {
+                    heat_bar.red().bold()
+                } else if heat >= overheat / 2 {
+                    heat_bar.light_red()
+                } else {
+                    heat_bar.yellow()
+                },
+            ])
+        }
+        (
+            Job::Technopriest { prayers },
+            Job::Technopriest {
+                prayers: max_prayers,
+            },
+        ) => Line::from(format!("{}{}", glyphs.prayers, pips(prayers, max_prayers)).light_green()),
+        (
+            Job::Clairvoyant { sun, moon },
+            Job::Clairvoyant {
+                sun: max_sun,
+                moon: max_moon,
+            },
+        ) => Line::from(vec![
+            format!("{}{}", glyphs.sun, pips(sun, max_sun)).yellow(),
+            format!(" {}{}", glyphs.moon, pips(moon, max_moon)).magenta(),
+        ]),
+        (
+            Job::Nanovampire { battery },
+            Job::Nanovampire {
+                battery: max_battery,
+            },
+        ) => {
+            let battery_bar = format!("{}{}", glyphs.battery, bar(battery, max_battery));
+            Line::from(if battery <= max_battery / 4 {
+                battery_bar.red().bold()
+            } else if battery <= max_battery / 2 {
+                battery_bar.yellow()
+            } else {
+                battery_bar.light_blue()
+            })
+        }
+        _ => Line::raw(""),
+    }
+}
+
+/// A list popup and a details box next to it, sitting on top of the actions box. The popup grows
+/// upwards to fit its rows, up to the top of the screen.
+fn popup_chunks(frame: &Frame, rect: Rect, width: u16, rows: usize) -> Rc<[Rect]> {
+    let [area] = Layout::vertical([Constraint::Length((rows as u16 + 2).max(6))])
+        .flex(Flex::End)
+        .areas(Rect {
+            height: rect.top() + 1,
+            ..frame.area()
+        });
+    Layout::horizontal(vec![Constraint::Length(width), Constraint::Length(40)])
         .horizontal_margin(4)
-        .split(
-            Layout::vertical(vec![Constraint::Length(6)])
-                .flex(Flex::End)
-                .vertical_margin(frame.area().height - rect.top() - 1)
-                .split(frame.area())[0],
-        )[0];
+        .split(area)
+}
+
+/// A skill's cost in the glyphs the party panel shows resources with
+fn cost_spans(cost: &Cost, glyphs: &Glyphs) -> Vec<Span<'static>> {
+    match cost {
+        Cost::Job(job) => match *job {
+            Job::Gunslinger { ammo } => vec![format!("{}{ammo}", glyphs.ammo).dark_gray()],
+            Job::Netrunner { ram, heat } => {
+                let mut spans = vec![format!("{ram}{}", glyphs.ram).blue()];
+                if heat > 0 {
+                    spans.push(format!(" {heat}{}", glyphs.heat).light_red());
+                }
+                spans
+            }
+            Job::Technopriest { prayers } => {
+                vec![format!("{}{prayers}", glyphs.prayers).light_green()]
+            }
+            Job::Clairvoyant { sun, moon } => {
+                let mut spans = Vec::new();
+                if sun > 0 {
+                    spans.push(format!("{}{sun}", glyphs.sun).yellow());
+                }
+                if moon > 0 {
+                    if !spans.is_empty() {
+                        spans.push(" ".into());
+                    }
+                    spans.push(format!("{}{moon}", glyphs.moon).magenta());
+                }
+                spans
+            }
+            Job::Nanovampire { battery } => {
+                vec![format!("{}{battery}%", glyphs.battery).light_blue()]
+            }
+            Job::None => Vec::new(),
+        },
+        Cost::Health(_) | Cost::HealthPercent(_) => vec![cost.to_string().red()],
+        Cost::All(job) => {
+            let mut spans = vec!["All ".into()];
+            spans.extend(cost_spans(&Cost::Job(*job), glyphs));
+            spans
+        }
+        Cost::Multiple(costs) => {
+            let mut spans = Vec::new();
+            for cost in costs {
+                if !spans.is_empty() {
+                    spans.push(" + ".into());
+                }
+                spans.extend(cost_spans(cost, glyphs));
+            }
+            spans
+        }
+    }
+}
+
+fn draw_details(frame: &mut Frame, rect: Rect, skill: &Skill) {
     frame.render_widget(Clear, rect);
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::raw(skill.description),
+            Line::from(skill.summary().dark_gray()),
+        ])
+        .wrap(Wrap { trim: true })
+        .block(Block::default().title("Details").borders(Borders::ALL)),
+        rect,
+    );
+}
 
+fn draw_skills(frame: &mut Frame, rect: Rect, app: &mut App) {
+    let caster = app
+        .turn
+        .expect("Can't get here unless it's someone's turrn");
     let mut skills_query = app
         .world
-        .query_one::<&Skills>(
-            app.turn
-                .expect("Can't get here unless it's someone's turrn"),
-        )
+        .query_one::<&Skills>(caster)
         .expect("Entity needs to exist in the world");
     let Skills(skills) = skills_query.get().expect("Entity needs skills");
-    let items = skills.iter().map(|i| i.name).collect::<Vec<_>>();
+
+    let chunks = popup_chunks(frame, rect, 32, skills.len());
+    let rect = chunks[0];
+    frame.render_widget(Clear, rect);
+
+    // Inside the borders
+    let width = rect.width.saturating_sub(2) as usize;
+    let items = skills
+        .iter()
+        .map(|skill| {
+            let cooldown = skill.cooldown_left(&app.world, caster);
+            let usable = cooldown == 0 && skill.can_afford(&app.world, caster);
+            let cost = if cooldown > 0 {
+                vec![format!("CD {cooldown}").into()]
+            } else {
+                cost_spans(skill.cost(), glyphs(app))
+            };
+            let cost_width = cost.iter().map(Span::width).sum::<usize>();
+            let mut line = Line::from(format!(
+                "{:<width$}",
+                skill.name,
+                width = width.saturating_sub(cost_width)
+            ));
+            line.extend(cost);
+            if usable {
+                ListItem::new(line)
+            } else {
+                // Spans keep their own colors unless they're all dimmed
+                ListItem::new(Line::from(
+                    line.spans
+                        .into_iter()
+                        .map(|span| span.dark_gray())
+                        .collect::<Vec<_>>(),
+                ))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(skill) = app.skill_list_state.selected().and_then(|i| skills.get(i)) {
+        draw_details(frame, chunks[1], skill);
+    }
 
     frame.render_stateful_widget(
         List::default()
             .items(items)
             .highlight_style(Style::new().reversed())
-            .block(Block::default().title("Skills ↓↑").borders(Borders::ALL)),
+            .block(
+                Block::default()
+                    .title(format!("Skills {}", glyphs(app).up_down))
+                    .borders(Borders::ALL),
+            ),
         rect,
         &mut app.skill_list_state,
     );
 }
 
+/// Narrowest and widest the item table gets, going by what's in it
+const MIN_ITEMS_WIDTH: u16 = 20;
+const MAX_ITEMS_WIDTH: u16 = 44;
+
 fn draw_items(frame: &mut Frame, rect: Rect, app: &mut App) {
-    let rect = Layout::horizontal(vec![Constraint::Length(20)])
-        .horizontal_margin(4)
-        .split(
-            Layout::vertical(vec![Constraint::Length(6)])
-                .flex(Flex::End)
-                .vertical_margin(frame.area().height - rect.top() - 1)
-                .split(frame.area())[0],
-        )[0];
+    // Name, who it targets and the count, with the spacing and borders around them
+    let width = app
+        .consumables
+        .iter()
+        .map(|item| item.name.len() + item.skill.target_name().len() + 4 + 2 + 2)
+        .max()
+        .unwrap_or(0) as u16;
+    let chunks = popup_chunks(
+        frame,
+        rect,
+        width.clamp(MIN_ITEMS_WIDTH, MAX_ITEMS_WIDTH),
+        app.consumables.len().max(1),
+    );
+    let rect = chunks[0];
     frame.render_widget(Clear, rect);
 
-    let widths = vec![Constraint::Fill(1), Constraint::Length(4)];
+    if let Some(item) = app
+        .consumable_list_state
+        .selected()
+        .and_then(|i| app.consumables.get(i))
+    {
+        draw_details(frame, chunks[1], item.skill);
+    }
+
+    let block = Block::default()
+        .title(format!("Items {}", glyphs(app).up_down))
+        .borders(Borders::ALL);
+    if app.consumables.is_empty() {
+        frame.render_widget(Paragraph::new("No items".dark_gray()).block(block), rect);
+        return;
+    }
+
+    let name_width = app
+        .consumables
+        .iter()
+        .map(|item| item.name.len())
+        .max()
+        .unwrap_or(0) as u16;
+    let widths = vec![
+        Constraint::Length(name_width),
+        Constraint::Fill(1),
+        Constraint::Length(4),
+    ];
     let rows = app
         .consumables
         .iter()
         .map(|i| {
             Row::new(vec![
                 Cell::from(i.name),
+                Cell::from(i.skill.target_name().dark_gray()),
                 Cell::from(Line::from(i.amount.to_string()).right_aligned()),
             ])
         })
@@ -378,64 +1283,269 @@ fn draw_items(frame: &mut Frame, rect: Rect, app: &mut App) {
     frame.render_stateful_widget(
         Table::new(rows, widths)
             .row_highlight_style(Style::default().reversed())
-            .block(Block::default().title("Items ↓↑").borders(Borders::ALL)),
+            .block(block),
         rect,
         &mut app.consumable_list_state,
     );
 }
 
+/// The highlighted target's stats and statuses, or a count of everyone the skill hits
+fn draw_target_preview(frame: &mut Frame, rect: Rect, app: &App) {
+    let (title, lines) = match app.selected_target {
+        Some(selected) => {
+            let target = app.targets[selected];
+            let Ok(mut query) = app
+                .world
+                .query_one::<(&Name, &Level, &Health, &Stats)>(target)
+            else {
+                return;
+            };
+            let Some((&Name(name), &Level(level), &Health(health), stats)) = query.get() else {
+                return;
+            };
+            let statuses = DEBUFFS
+                .iter()
+                .chain(BUFFS.iter())
+                .filter_map(|status| {
+                    (status.turns_left)(&app.world, target)
+                        .map(|turns| format!("{} ({turns})", status.name))
+                })
+                .collect::<Vec<_>>();
+            let mut lines = vec![
+                Line::from(vec![
+                    "HP ".dark_gray(),
+                    if health_hidden(app, target) {
+                        "???".into()
+                    } else {
+                        format!("{health}/{}", stats.max_health).into()
+                    },
+                ]),
+                Line::from(vec![
+                    "ATK ".dark_gray(),
+                    format!("{:<5}", stats.attack).into(),
+                    "DEF ".dark_gray(),
+                    format!("{:<5}", stats.defense).into(),
+                    "SPD ".dark_gray(),
+                    stats.speed.to_string().into(),
+                ]),
+                if statuses.is_empty() {
+                    Line::from("No statuses".dark_gray())
+                } else {
+                    Line::from(statuses.join(", "))
+                },
+            ];
+            if app
+                .world
+                .get::<&Scanned>(target)
+                .is_ok_and(|scanned| scanned.resistances)
+                && let Ok(resistances) = app.world.get::<&Resistances>(target)
+            {
+                let (weak, resists) = resistances.affinities();
+                for (label, damage_types) in [("Weak ", weak), ("Resists ", resists)] {
+                    if damage_types.is_empty() {
+                        continue;
+                    }
+                    let mut line = Line::from(label.dark_gray());
+                    for (i, damage_type) in damage_types.into_iter().enumerate() {
+                        if i > 0 {
+                            line.push_span(", ");
+                        }
+                        line.push_span(damage_type);
+                    }
+                    lines.push(line);
+                }
+            }
+            (format!("{name} Lv.{level}"), lines)
+        }
+        None => (
+            format!("{} targets", app.targets.len()),
+            app.targets
+                .iter()
+                .filter_map(|&target| {
+                    let name = app.world.get::<&Name>(target).ok()?.0;
+                    let health = app.world.get::<&Health>(target).ok()?.0;
+                    let health = if health_hidden(app, target) {
+                        "???".to_string()
+                    } else {
+                        health.to_string()
+                    };
+                    Some(Line::from(vec![
+                        format!("{name} ").into(),
+                        format!("{health} HP").dark_gray(),
+                    ]))
+                })
+                .collect(),
+        ),
+    };
+
+    let rect = popup_chunks(frame, rect, 32, lines.len())[0];
+    frame.render_widget(Clear, rect);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().title(title).borders(Borders::ALL)),
+        rect,
+    );
+}
+
 fn draw_footer(frame: &mut Frame, rect: Rect, app: &App) {
-    let current_navigation_text = match app.current_screen {
-        CurrentScreen::Main => "Select Action".green(),
-        CurrentScreen::Target => "Select Target for ".green(),
-        CurrentScreen::Skill => "Select Skill".green(),
-        CurrentScreen::Item => "Select Item".green(),
-        CurrentScreen::Enemy => "Enemy's Turn".blue(),
-        CurrentScreen::Exiting => "Exiting".light_red(),
+    // The highlighted skill or item says what it does in place of the screen's name
+    let highlighted = match app.current_screen {
+        CurrentScreen::Skill => app
+            .turn
+            .and_then(|turn| app.world.get::<&Skills>(turn).ok())
+            .and_then(|skills| {
+                let skill = *skills.0.get(app.skill_list_state.selected()?)?;
+                Some((skill.name, skill))
+            }),
+        CurrentScreen::Item => app
+            .consumable_list_state
+            .selected()
+            .and_then(|selected| app.consumables.get(selected))
+            .map(|item| (item.name, &**item.skill)),
+        _ => None,
+    };
+
+    let current_navigation_text = match (highlighted, app.current_screen) {
+        (Some((name, _)), _) => name.green(),
+        (None, CurrentScreen::Main) => "Select Action".green(),
+        (None, CurrentScreen::Target) => "Select Target for ".green(),
+        (None, CurrentScreen::Skill) => "Select Skill".green(),
+        (None, CurrentScreen::Item) => "Select Item".green(),
+        (None, CurrentScreen::Enemy) => "Enemy's Turn".blue(),
+        (None, CurrentScreen::Help) => "Help".cyan(),
     };
 
-    let secondary_text = if matches!(app.current_screen, CurrentScreen::Target)
+    let secondary_text = if let Some((_, skill)) = highlighted {
+        let description = skill.description.trim_end_matches('.');
+        Span::from(match skill.cost() {
+            Cost::Job(Job::None) => format!(" — {description}"),
+            cost => format!(" — {description}, costs {cost}"),
+        })
+        .blue()
+    } else if matches!(app.current_screen, CurrentScreen::Target)
         && let Some(skill) = app.skill
     {
-        skill.name.blue()
+        Span::from(format!("{} — {}", skill.name, skill.description)).blue()
+    } else if matches!(app.current_screen, CurrentScreen::Main)
+        && app
+            .action_list_state
+            .selected()
+            .and_then(|selected| app.action_list_items.get(selected))
+            .is_some_and(|item| matches!(item.action, Action::Flee))
+    {
+        Span::from(format!(
+            " — {:.0}% chance to escape",
+            app.flee_chance() * 100.
+        ))
+        .blue()
     } else {
         "".into()
     };
 
-    let mode_footer = Paragraph::new(Line::from(vec![current_navigation_text, secondary_text]))
-        .block(Block::default().borders(Borders::ALL));
-
-    let current_keys_hint = {
-        match app.current_screen {
-            CurrentScreen::Main => "(q) to quit / (↓↑) to select action".red(),
-            CurrentScreen::Skill => "(esc) to cancel / (↓↑) to select skill".red(),
-            CurrentScreen::Item => "(esc) to cancel / (↓↑) to select item".red(),
-            CurrentScreen::Target => "(esc) to cancel / (←→) to select target".red(),
-            CurrentScreen::Enemy => "Wait for enemy to finish turn".red(),
-            CurrentScreen::Exiting => "(q) to quit".red(),
-        }
+    let warning_text = if matches!(app.current_screen, CurrentScreen::Target)
+        && app.turn.is_some_and(|turn| app.is_confused(turn))
+    {
+        " (Confused — target may change)".light_red()
+    } else {
+        "".into()
     };
 
-    let key_notes_footer =
-        Paragraph::new(Line::from(current_keys_hint)).block(Block::default().borders(Borders::ALL));
-
     let footer_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(rect);
 
+    let mode_line = match app.notice {
+        Some(notice) => Line::from(notice.light_red()),
+        None if matches!(app.game_state, GameState::Victory) => Line::from("Victory".green()),
+        None if matches!(app.game_state, GameState::GameOver) => {
+            Line::from("Game Over".light_red())
+        }
+        None => Line::from(vec![current_navigation_text, secondary_text, warning_text]),
+    };
+    // A single line between the borders, so long descriptions get cut short rather than wrapped
+    let mode_footer = Paragraph::new(ellipsize(
+        mode_line,
+        footer_chunks[0].width.saturating_sub(2),
+    ))
+    .block(Block::default().borders(Borders::ALL));
+
+    let quit = app.keys.hint(&[Message::Quit]);
+    let cancel = app.keys.hint(&[Message::Cancel]);
+    let select = app.keys.hint(&[Message::Select]);
+    let vertical = app.keys.hint(&[Message::Down, Message::Up]);
+    let horizontal = app.keys.hint(&[Message::Left, Message::Right]);
+    let current_keys_hint = if let Some(confirmation) = &app.confirmation {
+        let confirm = app
+            .keys
+            .hint(&[confirmation.confirm_with.unwrap_or(Message::Select)]);
+        format!("{confirm} to confirm / {cancel} to cancel")
+    } else if matches!(app.game_state, GameState::Victory) {
+        format!("{select} to continue")
+    } else if matches!(app.game_state, GameState::GameOver) {
+        format!("{vertical} to select / {select} to confirm")
+    } else {
+        match app.current_screen {
+            CurrentScreen::Main => format!("{quit} to quit / {vertical} to select action"),
+            CurrentScreen::Skill => format!("{cancel} to cancel / {vertical} to select skill"),
+            CurrentScreen::Item => format!("{cancel} to cancel / {vertical} to select item"),
+            CurrentScreen::Target => format!("{cancel} to cancel / {horizontal} to select target"),
+            CurrentScreen::Enemy => format!("{quit} to quit / {select} to skip the wait"),
+            CurrentScreen::Help => format!(
+                "{} to close / {vertical} to scroll",
+                app.keys.hint(&[Message::Help, Message::Cancel])
+            ),
+        }
+    }
+    .red();
+
+    let key_notes_footer =
+        Paragraph::new(Line::from(current_keys_hint)).block(Block::default().borders(Borders::ALL));
+
     frame.render_widget(mode_footer, footer_chunks[0]);
     frame.render_widget(key_notes_footer, footer_chunks[1]);
 }
 
+/// Cuts the line down to the width, ending it with an ellipsis if anything was left out
+fn ellipsize(line: Line<'static>, width: u16) -> Line<'static> {
+    let width = width as usize;
+    if line.width() <= width {
+        return line;
+    }
+    let Line {
+        spans,
+        style,
+        alignment,
+    } = line;
+    let mut cut = Line {
+        spans: Vec::new(),
+        style,
+        alignment,
+    };
+    let mut room = width.saturating_sub(1);
+    for span in spans {
+        let length = span.content.chars().count();
+        if length <= room {
+            room -= length;
+            cut.push_span(span);
+            continue;
+        }
+        let content = span.content.chars().take(room).collect::<String>();
+        cut.push_span(Span::styled(content, span.style));
+        break;
+    }
+    cut.push_span("…");
+    cut
+}
+
 fn draw_popup(frame: &mut Frame, app: &App) {
-    if let CurrentScreen::Exiting = app.current_screen {
-        let popup_block = Block::default().title("Really quit?").borders(Borders::ALL);
+    if let Some(confirmation) = &app.confirmation {
+        let popup_block = Block::default()
+            .title(confirmation.title)
+            .borders(Borders::ALL);
 
-        let exit_text = Text::styled(
-            "Press (q) again to confirm",
-            Style::default().fg(Color::Red),
-        );
+        let exit_text = Text::styled(confirmation.body.clone(), Style::default().fg(Color::Red));
         // the `trim: false` will stop the text from being cut off when over the edge of the block
         let exit_paragraph = Paragraph::new(exit_text)
             .block(popup_block)
@@ -447,6 +1557,86 @@ fn draw_popup(frame: &mut Frame, app: &App) {
     }
 }
 
+/// What each job's resources are for, in the order of the party
+const JOB_HELP: [(&str, &str); 5] = [
+    (
+        "Gunslinger",
+        "Shots come out of a six-round cylinder. Reload to fill it back up.",
+    ),
+    (
+        "Netrunner",
+        "Programs take RAM and run hot. At 100 heat the next turn is lost to a shutdown. \
+         Heat sheds a little every turn, and Vent dumps it all.",
+    ),
+    (
+        "Technopriest",
+        "Regains a prayer every turn, and can hold more of them every other level.",
+    ),
+    (
+        "Clairvoyant",
+        "Light gathers suns and dark gathers moons, for the stronger spells to spend.",
+    ),
+    (
+        "Nanovampire",
+        "The battery runs down every turn. Feeding on enemies charges it back up.",
+    ),
+];
+
+fn screen_help(screen: CurrentScreen) -> &'static str {
+    match screen {
+        CurrentScreen::Main => "Pick what the current party member does this turn.",
+        CurrentScreen::Skill => {
+            "Pick a skill to use. Greyed out skills are on cooldown or cost more than is left."
+        }
+        CurrentScreen::Item => "Pick an item to use. Items are shared by the whole party.",
+        CurrentScreen::Target => "Pick who the skill or item lands on.",
+        CurrentScreen::Enemy => "The enemy is acting. Any key hurries them along.",
+        CurrentScreen::Help => "",
+    }
+}
+
+fn draw_help(frame: &mut Frame, app: &mut App) {
+    if !matches!(app.current_screen, CurrentScreen::Help) {
+        return;
+    }
+    let screen = app
+        .previous_screen
+        .last()
+        .copied()
+        .unwrap_or(CurrentScreen::Main);
+
+    let mut lines = vec![
+        Line::from(screen_help(screen)),
+        Line::default(),
+        Line::from("Keys".bold()),
+    ];
+    for (action, keys) in app.keys.actions() {
+        lines.push(Line::from(vec![
+            format!("{:<10}", action.replace('_', " ")).dark_gray(),
+            keys.into(),
+        ]));
+    }
+    lines.extend([Line::default(), Line::from("Jobs".bold())]);
+    for (job, help) in JOB_HELP {
+        lines.push(Line::from(vec![format!("{job} ").cyan(), help.into()]));
+    }
+
+    let area = centered_rect(70, 70, frame.area());
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+    // Borders take up two rows of the area
+    let rows = paragraph.line_count(area.width.saturating_sub(2)) as u16;
+    app.help_scroll = app
+        .help_scroll
+        .min(rows.saturating_sub(area.height.saturating_sub(2)));
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        paragraph
+            .block(Block::default().title("Help").borders(Borders::ALL))
+            .scroll((app.help_scroll, 0)),
+        area,
+    );
+}
+
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     // Cut the given rectangle into three vertical pieces
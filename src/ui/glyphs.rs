@@ -0,0 +1,89 @@
+/// Icons the UI draws with. The ASCII set is for terminals without the fonts for the rest.
+pub struct Glyphs {
+    /// Marks a targeted enemy, from above
+    pub target_enemy: &'static str,
+    /// Marks a targeted party member, from the left
+    pub target_ally: &'static str,
+    pub phase_done: &'static str,
+    pub phase_left: &'static str,
+    pub charging: &'static str,
+    pub defending: &'static str,
+    pub dead: &'static str,
+    pub pending: &'static str,
+    /// Between a status's stacks and its turns
+    pub times: &'static str,
+    pub ammo: &'static str,
+    pub ram: &'static str,
+    pub heat: &'static str,
+    pub prayers: &'static str,
+    pub sun: &'static str,
+    pub moon: &'static str,
+    pub battery: &'static str,
+    /// Counted resources, one pip each
+    pub pip_full: &'static str,
+    pub pip_empty: &'static str,
+    /// Percent-like resources, as a bar
+    pub bar_full: &'static str,
+    pub bar_empty: &'static str,
+    pub up_down: &'static str,
+    pub more_above: &'static str,
+    pub more_below: &'static str,
+    pub more_left: &'static str,
+    pub more_right: &'static str,
+}
+
+pub const UNICODE: Glyphs = Glyphs {
+    target_enemy: "⮟",
+    target_ally: "⮞",
+    phase_done: "◆",
+    phase_left: "◇",
+    charging: "✧",
+    defending: "⛨",
+    dead: "✝",
+    pending: "⏳",
+    times: "×",
+    ammo: "⁍ ",
+    ram: "GB",
+    heat: "ºC",
+    prayers: "✠ ",
+    sun: "☀ ",
+    moon: "☽︎ ",
+    battery: "⚡",
+    pip_full: "▮",
+    pip_empty: "▯",
+    bar_full: "█",
+    bar_empty: "░",
+    up_down: "↓↑",
+    more_above: "▲",
+    more_below: "▼",
+    more_left: "◀",
+    more_right: "▶",
+};
+
+pub const ASCII: Glyphs = Glyphs {
+    target_enemy: "v",
+    target_ally: ">",
+    phase_done: "#",
+    phase_left: "-",
+    charging: "*",
+    defending: "[D]",
+    dead: "DEAD",
+    pending: "~",
+    times: "x",
+    ammo: "ammo:",
+    ram: "GB",
+    heat: "C",
+    prayers: "pray:",
+    sun: "sun:",
+    moon: "moon:",
+    battery: "bat:",
+    pip_full: "#",
+    pip_empty: "-",
+    bar_full: "=",
+    bar_empty: ".",
+    up_down: "v^",
+    more_above: "^",
+    more_below: "v",
+    more_left: "<",
+    more_right: ">",
+};